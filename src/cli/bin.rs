@@ -7,6 +7,8 @@ pub mod conv;
 
 
 use crate::prelude::*;
+use crate::deriv::{ Lexicon, is_derivation };
+use crate::labels::{ BasicTriggers };
 
 use std::collections::{ HashMap };
 use std::fmt;
@@ -173,6 +175,7 @@ struct Engine {
     map: HashMap<String, (Type, Value)>,
     lex: Option<Lexicon>,
     ug: Option<UniversalGrammar<BasicTriggers>>,
+    phase_heads: Option<Set<Feature>>,
     il: Option<ILanguage<BasicTriggers>>,
 }
 
@@ -182,6 +185,7 @@ impl Engine {
             map: HashMap::new(),
             lex: None,
             ug: None,
+            phase_heads: None,
             il: None,
         }
     }
@@ -208,7 +212,7 @@ impl Engine {
     }
 
     fn set(&mut self, id: String, expr: Expr) -> Result<(), ()> {
-        if id != "lex" && id != "ug" {
+        if id != "lex" && id != "ug" && id != "phase_heads" {
             my_error!("set: Unknown global variable `{}`.", id);
             return Err(());
         }
@@ -265,6 +269,32 @@ impl Engine {
             self.ug = Some(ug);
             return Ok(());
         }
+        else if id == "phase_heads" {
+            if let Err(_) = tyck::tyck(&Type::Set(Box::new(Type::Feature)), &expr, &self.map) {
+                my_error!("set: Type error.");
+                return Err(());
+            }
+        
+            let val = match eval::eval(expr, &self.map) {
+                Ok(val) => val,
+                _ => {
+                    my_error!("set: Evaluation error.");
+                    return Err(());
+                },
+            };
+
+            let phase_heads = match conv::to_feature_set(val) {
+                Ok(phase_heads) => phase_heads,
+                _ => {
+                    my_error!("set: Value conversion error.");
+                    return Err(());
+                },
+            };
+
+            my_info!("set: Setting the phase heads to {:?}", phase_heads);
+            self.phase_heads = Some(phase_heads);
+            return Ok(());
+        }
 
         unreachable!()
     }
@@ -276,7 +306,12 @@ impl Engine {
         }
 
         let lex = std::mem::replace(&mut self.lex, None).unwrap();
-        let ug = std::mem::replace(&mut self.ug, None).unwrap();
+        let mut ug = std::mem::replace(&mut self.ug, None).unwrap();
+
+        //  The phase heads are C and v* unless they are set.
+        if let Some(phase_heads) = std::mem::replace(&mut self.phase_heads, None) {
+            ug.phase_heads = phase_heads;
+        }
 
         self.il = Some(ILanguage { lex, ug });
 
@@ -311,7 +346,7 @@ impl Engine {
         };
 
         my_info!("Checking the derivation...");
-        if is_derivation::<BasicTriggers>(self.il.as_ref().unwrap(), &derivation) {
+        if is_derivation::<BasicTriggers, SyntacticObject>(self.il.as_ref().unwrap(), &derivation) {
             my_info!("Valid derivation.");
         }
         else {
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use crate::deriv::{ Lexicon, LexicalArray };
 
 use super::Value;
 
@@ -127,6 +128,11 @@ pub fn to_lexicon(val: Value) -> Result<Lexicon, ()> {
     }
 }
 
+//  {F}
+pub fn to_feature_set(val: Value) -> Result<Set<Feature>, ()> {
+    to_set(val, to_feature)
+}
+
 //  UG := <{F}, {F}, {F}>
 pub fn to_ug<T: Triggers>(val: Value) -> Result<UniversalGrammar<T>, ()> {
     match val {
@@ -197,7 +203,7 @@ pub fn to_stage(val: Value) -> Result<Stage, ()> {
             let la = to_set(it.next().unwrap(), to_lexical_item_token)?;
             let w = to_set(it.next().unwrap(), to_so)?;
 
-            Ok(Stage { la, w: Workspace::new(w) })
+            Ok(Stage { la: LexicalArray::new(la), w: Workspace::new(w) })
         },
 
         _ => Err(()),
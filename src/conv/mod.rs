@@ -1,35 +1,113 @@
 use crate::prelude::*;
 
+use std::fmt;
 
 
-pub fn converges_ci(deriv: &[Stage]) -> bool {
-    if let Some(stage) = deriv.last() {
-        if stage.w.0.len() == 1 {
-            if let SyntacticObject::Transfer { .. } = stage.w.0.first().unwrap() {
-                return true;
-            }
+
+/// The reason why a derivation does not converge at an interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvergenceError {
+    /// The derivation has no stages.
+    NoStages,
+    /// The lexical array of the last stage still has lexical item tokens.
+    LexicalArrayNotExhausted,
+    /// The workspace of the last stage does not consist of exactly one transferred syntactic object.
+    NotTransferred,
+    /// The triggers of the transferred syntactic object are undefined for this reason.
+    UndefinedTriggers(CandsError),
+    /// The transferred syntactic object still has these trigger features unchecked.
+    UncheckedTriggers(Set<Feature>),
+    /// The LF of the transferred syntactic object has a feature that is not in SEM-F.
+    NotSemantic(Feature),
+    /// The PF of the transferred syntactic object has a feature that is not in PHON-F.
+    NotPhonological(Feature),
+}
+
+
+
+impl fmt::Display for ConvergenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvergenceError::NoStages =>
+                write!(f, "The derivation must have >= 1 stages."),
+            ConvergenceError::LexicalArrayNotExhausted =>
+                write!(f, "The lexical array of the last stage must be empty."),
+            ConvergenceError::NotTransferred =>
+                write!(f, "The workspace of the last stage must consist of exactly one transferred syntactic object."),
+            ConvergenceError::UndefinedTriggers(e) =>
+                write!(f, "The triggers of the transferred syntactic object are undefined. {}", e),
+            ConvergenceError::UncheckedTriggers(tfs) =>
+                write!(f, "The transferred syntactic object has unchecked trigger features: {{{}}}", tfs.iter().map(|tf| tf.to_string()).collect::<Vec<_>>().join(", ")),
+            ConvergenceError::NotSemantic(feature) =>
+                write!(f, "The LF has a feature that is not in SEM-F: {}", feature),
+            ConvergenceError::NotPhonological(feature) =>
+                write!(f, "The PF has a feature that is not in PHON-F: {}", feature),
         }
     }
+}
+
+
+
+/// Check the conditions that both interfaces impose on the derivation `deriv`, and return the transferred syntactic object of its last stage.
+///
+/// The lexical array of the last stage must be exhausted, its workspace must consist of exactly one transferred syntactic object, and that syntactic object must have no unchecked trigger features.
+fn check_last_stage<'a, T: Triggers>(ug: &UniversalGrammar<T>, deriv: &'a [Stage]) -> Result<&'a SyntacticObject, ConvergenceError> {
+    let Stage { la, w } = deriv.last()
+        .ok_or(ConvergenceError::NoStages)?;
+
+    if !la.is_empty() {
+        return Err(ConvergenceError::LexicalArrayNotExhausted);
+    }
+
+    let root = match w.iter().next() {
+        Some(root @ SyntacticObject::Transfer { .. }) if w.len() == 1 => root,
+        _ => return Err(ConvergenceError::NotTransferred),
+    };
 
-    false
+    let tfs = ug.triggers.triggers(root, w)
+        .map_err(ConvergenceError::UndefinedTriggers)?;
+
+    if !tfs.is_empty() {
+        return Err(ConvergenceError::UncheckedTriggers(tfs));
+    }
+
+    Ok(root)
 }
 
 
 
-pub fn converges_sm(deriv: &[Stage]) -> bool {
-    if let Some(stage) = deriv.last() {
-        if stage.w.0.len() == 1 {
-            if let SyntacticObject::Transfer { .. } = stage.w.0.first().unwrap() {
-                return true;
-            }
+/// Check if the derivation `deriv` converges at the conceptual-intentional (CI) interface.
+///
+/// The lexical array of the last stage must be exhausted, and its workspace must consist of exactly one transferred syntactic object, which has no unchecked trigger features, and whose LF only has features in SEM-F.
+pub fn converges_ci<T: Triggers>(ug: &UniversalGrammar<T>, deriv: &[Stage]) -> Result<(), ConvergenceError> {
+    if let SyntacticObject::Transfer { lf, .. } = check_last_stage(ug, deriv)? {
+        if let Some(feature) = lf.iter().find(|&f| !ug.sem_f.contains(f)) {
+            return Err(ConvergenceError::NotSemantic(feature.clone()));
         }
     }
 
-    false
+    Ok(())
 }
 
 
 
-pub fn converges(deriv: &[Stage]) -> bool {
-    converges_ci(deriv) && converges_sm(deriv)
-}
\ No newline at end of file
+/// Check if the derivation `deriv` converges at the sensory-motor (SM) interface.
+///
+/// The lexical array of the last stage must be exhausted, and its workspace must consist of exactly one transferred syntactic object, which has no unchecked trigger features, and whose PF only has features in PHON-F.
+pub fn converges_sm<T: Triggers>(ug: &UniversalGrammar<T>, deriv: &[Stage]) -> Result<(), ConvergenceError> {
+    if let SyntacticObject::Transfer { pf, .. } = check_last_stage(ug, deriv)? {
+        if let Some(feature) = pf.iter().find(|&f| !ug.phon_f.contains(f)) {
+            return Err(ConvergenceError::NotPhonological(feature.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+
+
+/// Check if the derivation `deriv` converges at both interfaces. See [`converges_ci`] and [`converges_sm`].
+pub fn converges<T: Triggers>(ug: &UniversalGrammar<T>, deriv: &[Stage]) -> Result<(), ConvergenceError> {
+    converges_ci(ug, deriv)?;
+    converges_sm(ug, deriv)
+}
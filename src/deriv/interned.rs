@@ -0,0 +1,248 @@
+use crate::prelude::*;
+
+use once_cell::sync::{ Lazy };
+
+use std::cmp::Ordering;
+use std::collections::{ HashMap };
+use std::fmt;
+use std::hash::{ Hash, Hasher };
+use std::sync::{ Arc, Mutex, Weak };
+
+
+
+/// What an interned syntactic object is made of. Its members are interned themselves, so comparing and hashing this only takes time linear in the number of its members.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Kind {
+    LexicalItemToken(LexicalItemToken),
+    Set(Set<InternedSO>),
+    Transfer {
+        so: InternedSO,
+        pf: Vec<Feature>,
+        lf: Set<Feature>,
+    },
+}
+
+
+
+#[derive(Debug)]
+struct Node {
+    id: usize,
+    kind: Kind,
+}
+
+
+
+/// The interner, which maps every syntactic object that is still in use to its node.
+struct Interner {
+    nodes: HashMap<Kind, Weak<Node>>,
+    next_id: usize,
+    /// The number of nodes right after the last time that the nodes that are no longer in use were dropped.
+    live: usize,
+}
+
+
+
+static INTERNER: Lazy<Mutex<Interner>> = Lazy::new(|| Mutex::new(Interner {
+    nodes: HashMap::new(),
+    next_id: 0,
+    live: 0,
+}));
+
+
+
+/// An interned (hash-consed) syntactic object.
+///
+/// Every distinct syntactic object is stored only once, behind an [`Arc`], and has a unique id. So cloning, comparing and hashing interned syntactic objects takes constant time, and equal syntactic objects share all their memory.
+///
+/// Interned syntactic objects are ordered by their ids, i.e. by the order in which they were first interned, not by their structure like [`SyntacticObject`].
+#[derive(Clone)]
+pub struct InternedSO(Arc<Node>);
+
+
+
+impl InternedSO {
+    fn intern(kind: Kind) -> Self {
+        let mut interner = INTERNER.lock().unwrap();
+
+        if let Some(node) = interner.nodes.get(&kind).and_then(Weak::upgrade) {
+            return InternedSO(node);
+        }
+
+        //  Drop the nodes that are no longer in use once the interner has doubled in size.
+        if interner.nodes.len() >= 2 * interner.live.max(1024) {
+            interner.nodes.retain(|_, node| node.strong_count() > 0);
+            interner.live = interner.nodes.len();
+        }
+
+        let node = Arc::new(Node { id: interner.next_id, kind: kind.clone() });
+        interner.next_id += 1;
+        interner.nodes.insert(kind, Arc::downgrade(&node));
+        InternedSO(node)
+    }
+
+    /// The unique id of `self`.
+    pub fn id(&self) -> usize {
+        self.0.id
+    }
+}
+
+
+
+impl PartialEq for InternedSO {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for InternedSO {}
+
+impl PartialOrd for InternedSO {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InternedSO {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id().cmp(&other.id())
+    }
+}
+
+impl Hash for InternedSO {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+
+
+impl fmt::Debug for InternedSO {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "InternedSO({}, {:?})", self.id(), self.0.kind)
+    }
+}
+
+
+
+impl fmt::Display for InternedSO {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", SOPrefixFormatter::new(self, 0))
+    }
+}
+
+
+
+impl SO for InternedSO {
+    fn view(&self) -> SOView<'_, Self> {
+        match &self.0.kind {
+            Kind::LexicalItemToken(lit) => SOView::LexicalItemToken(lit),
+            Kind::Set(set) => SOView::Set(set),
+            Kind::Transfer { so, pf, lf } => SOView::Transfer { so, pf, lf },
+        }
+    }
+
+    fn from_lit(lit: LexicalItemToken) -> Self {
+        Self::intern(Kind::LexicalItemToken(lit))
+    }
+
+    fn from_set(set: Set<Self>) -> Self {
+        Self::intern(Kind::Set(set))
+    }
+
+    fn from_transfer(so: Self, pf: Vec<Feature>, lf: Set<Feature>) -> Self {
+        Self::intern(Kind::Transfer { so, pf, lf })
+    }
+
+    //  Since equal syntactic objects are identical, there is no need to compare the syntactic objects further down.
+    fn immediately_contains(&self, a: &Self) -> bool {
+        match &self.0.kind {
+            Kind::LexicalItemToken(_) => false,
+            Kind::Set(b) => b.iter().any(|so| so == a),
+            Kind::Transfer { so, .. } => so == a,
+        }
+    }
+}
+
+
+
+impl From<&SyntacticObject> for InternedSO {
+    fn from(so: &SyntacticObject) -> Self {
+        match so {
+            SyntacticObject::LexicalItemToken(lit) =>
+                Self::from_lit(lit.clone()),
+            SyntacticObject::Set(set) =>
+                Self::from_set(set.iter().map(Self::from).collect()),
+            SyntacticObject::Transfer { so, pf, lf } =>
+                Self::from_transfer(Self::from(&**so), pf.clone(), lf.clone()),
+        }
+    }
+}
+
+
+
+impl From<&InternedSO> for SyntacticObject {
+    fn from(so: &InternedSO) -> Self {
+        match so.view() {
+            SOView::LexicalItemToken(lit) =>
+                so!(lit.clone()),
+            SOView::Set(set) =>
+                SyntacticObject::Set(set.iter().map(Self::from).collect()),
+            SOView::Transfer { so, pf, lf } =>
+                so!(Self::from(so) => pf.to_vec(); lf.clone()),
+        }
+    }
+}
+
+
+
+impl From<&Stage> for Stage<InternedSO> {
+    fn from(stage: &Stage) -> Self {
+        Stage {
+            la: stage.la.clone(),
+            w: Workspace::new(stage.w.iter().map(InternedSO::from).collect()),
+        }
+    }
+}
+
+
+
+impl From<&Stage<InternedSO>> for Stage {
+    fn from(stage: &Stage<InternedSO>) -> Self {
+        Stage {
+            la: stage.la.clone(),
+            w: Workspace::new(stage.w.iter().map(SyntacticObject::from).collect()),
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    mod interned {
+        use crate::prelude::*;
+        use crate::deriv::interned::{ InternedSO };
+
+
+
+        #[test]
+        fn sharing() {
+            let john = lit!(li!("John"; "D"; "John"), 1);
+            let helped = lit!(li!("help"; "V", "=D"; "helped"), 3);
+            let vp = so!(so!(helped.clone()), so!(john.clone()),);
+
+            let vp1 = InternedSO::from(&vp);
+            let vp2 = InternedSO::from_set(set!( InternedSO::from_lit(john.clone()), InternedSO::from_lit(helped.clone()) ));
+            assert_eq!(vp1, vp2);
+            assert_eq!(vp1.id(), vp2.id());
+
+            let john1 = InternedSO::from_lit(john.clone());
+            assert!(vp1.immediately_contains(&john1));
+            assert!(vp1.contains(&john1));
+            assert_ne!(vp1, john1);
+
+            //  Round trip.
+            assert_eq!(SyntacticObject::from(&vp1), vp);
+        }
+    }
+}
@@ -37,6 +37,13 @@ impl LexicalItem {
     ) -> Self {
         Self { sem, syn, phon, shorthand }
     }
+
+    /// Check if `self` and `other` are the same triple $\\langle \\textrm{SEM}, \\textrm{SYN}, \\textrm{PHON} \\rangle$, i.e. if they only differ in their shorthands, if at all.
+    pub fn has_same_features_as(&self, other: &LexicalItem) -> bool {
+        (self.sem == other.sem) &&
+        (self.syn == other.syn) &&
+        (self.phon == other.phon)
+    }
 }
 
 
@@ -128,6 +135,7 @@ macro_rules! li {
     };
 }
 
+#[cfg(test)]
 pub(crate) use li;
 
 
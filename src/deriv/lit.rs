@@ -55,6 +55,7 @@ macro_rules! lit {
     };
 }
 
+#[cfg(test)]
 pub(crate) use lit;
 
 
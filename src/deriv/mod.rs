@@ -1,15 +1,22 @@
 pub mod li;
 pub mod lit;
 pub mod so;
+pub mod occ;
+pub mod search;
+pub mod replay;
+pub mod tree;
+pub mod interned;
 
 
 
 use crate::prelude::*;
 
 use derive_more::{ Deref, DerefMut };
+use itertools::Itertools;
 
-use std::marker::PhantomData;
 use std::fmt;
+use std::sync::atomic::{ AtomicUsize, Ordering as AtomicOrdering };
+use std::thread;
 
 
 
@@ -18,21 +25,67 @@ use std::fmt;
 /// From Definition 1 in C&S 2016, p. 44.
 /// 
 /// >Universal Grammar is a 6-tuple: $\\langle \\textrm{PHON-F}, \\textrm{SYN-F}, \\textrm{SEM-F}, \\textrm{Select}, \\textrm{Merge}, \\textrm{Transfer} \\rangle$.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UniversalGrammar<T: Triggers> {
     pub phon_f:     Set<Feature>,
     pub syn_f:      Set<Feature>,
     pub sem_f:      Set<Feature>,
-    t:              PhantomData<T>,
+    /// The features of the heads of strong phases. A syntactic object is a strong phase if it is a maximal projection of a lexical item token with one of these features in its SYN. C and v* by default (see [`default_phase_heads`]).
+    pub phase_heads: Set<Feature>,
+    /// How strictly Derive-by-Transfer is checked. [`TransferPolicy::Strict`] by default.
+    pub transfer_policy: TransferPolicy,
+    /// Which Phase Impenetrability Condition holds. [`PicPolicy::Pic1`] by default.
+    pub pic_policy: PicPolicy,
+    /// The trigger features and labels. `T::default()` by default.
+    pub triggers: T,
 }
 
 impl<T: Triggers> UniversalGrammar<T> {
     pub fn new(phon_f: Set<Feature>, syn_f: Set<Feature>, sem_f: Set<Feature>) -> Self {
         Self {
-            phon_f, syn_f, sem_f, t: PhantomData::default()
+            phon_f, syn_f, sem_f,
+            phase_heads: default_phase_heads(),
+            transfer_policy: TransferPolicy::Strict,
+            pic_policy: PicPolicy::Pic1,
+            triggers: T::default(),
         }
     }
+
+    /// Look for likely mistakes in the trigger features with respect to SYN-F. See [`Triggers::validate`].
+    ///
+    /// The warnings are also logged whenever a derivation is checked, e.g. by [`check_derivation`].
+    pub fn trigger_warnings(&self) -> Vec<TriggersWarning> {
+        self.triggers.validate(&self.syn_f)
+    }
+}
+
+
+
+/// How strictly [`check_derivation`] checks Derive-by-Transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransferPolicy {
+    /// A strong phase cannot be transferred if it contains another strong phase whose complement has not been transferred, as in C&S 2016.
+    Strict,
+    /// A strong phase can be transferred no matter which strong phases it contains. This is how Derive-by-Transfer used to be checked.
+    Permissive,
+}
+
+
+
+/// Which Phase Impenetrability Condition (PIC) restricts access to the transferred parts of a syntactic object.
+///
+/// The PIC decides which syntactic objects contained in A can be B in Merge(A, B) (see [`check_derivation`]), which can be labeled (see [`LabeledWorkspace::label_of`]) and what can be c-commanded (see [`SO::c_commands`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PicPolicy {
+    /// There is no PIC. Everything inside a transferred syntactic object stays accessible.
+    None,
+    /// PIC1 (Chomsky 2000). Nothing inside a transferred syntactic object is accessible once it is transferred, as in C&S 2016.
+    Pic1,
+    /// PIC2 (Chomsky 2001). The transferred complement of a phase head H stays accessible until the next phase head is merged, i.e. it is inaccessible from a syntactic object whose path to it goes through a projection of a phase head other than H.
+    Pic2,
 }
 
 
@@ -59,12 +112,98 @@ pub struct ILanguage<T: Triggers> {
 
 
 
+impl<T: Triggers> ILanguage<T> {
+    /// Check if every lexical item in the lexicon is a lexical item with respect to UG.
+    ///
+    /// From Definition 2, C&S 2016, p. 44. A lexical item $\\langle \\textrm{SEM}, \\textrm{SYN}, \\textrm{PHON} \\rangle$ must satisfy $\\textrm{SEM} \\subseteq \\textrm{SEM-F}$, $\\textrm{SYN} \\subseteq \\textrm{SYN-F}$, and $\\textrm{PHON} \\in \\textrm{PHON-F}$*.
+    ///
+    /// Return every use of a feature that UG does not declare, if there is any.
+    pub fn validate(&self) -> Result<(), Vec<UndeclaredFeature>> {
+        let UniversalGrammar { phon_f, syn_f, sem_f, .. } = &self.ug;
+
+        let undeclared: Vec<UndeclaredFeature> = self.lex.iter()
+            .flat_map(|li| {
+                let sem = li.sem.iter()
+                    .filter(move |f| !sem_f.contains(f))
+                    .map(move |f| UndeclaredFeature::new(li, FeatureComponent::Sem, f));
+                let syn = li.syn.iter()
+                    .filter(move |f| !syn_f.contains(f))
+                    .map(move |f| UndeclaredFeature::new(li, FeatureComponent::Syn, f));
+                let phon = li.phon.iter()
+                    .filter(move |f| !phon_f.contains(f))
+                    .map(move |f| UndeclaredFeature::new(li, FeatureComponent::Phon, f));
+
+                sem.chain(syn).chain(phon)
+            })
+            .collect();
+
+        if undeclared.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(undeclared)
+        }
+    }
+}
+
+
+
+/// A component of a lexical item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureComponent {
+    Sem,
+    Syn,
+    Phon,
+}
+
+
+
+impl fmt::Display for FeatureComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatureComponent::Sem => write!(f, "SEM"),
+            FeatureComponent::Syn => write!(f, "SYN"),
+            FeatureComponent::Phon => write!(f, "PHON"),
+        }
+    }
+}
+
+
+
+/// A feature that a lexical item uses in one of its components, but UG does not declare.
+///
+/// See [`ILanguage::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndeclaredFeature {
+    pub li: LexicalItem,
+    pub component: FeatureComponent,
+    pub feature: Feature,
+}
+
+
+
+impl UndeclaredFeature {
+    fn new(li: &LexicalItem, component: FeatureComponent, feature: &Feature) -> Self {
+        Self { li: li.clone(), component, feature: feature.clone() }
+    }
+}
+
+
+
+impl fmt::Display for UndeclaredFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The lexical item {} uses {} as a {} feature, but UG does not declare it in {}-F.", self.li, self.feature, self.component, self.component)
+    }
+}
+
+
+
 /// Lexical array.
 /// 
 /// From Definition 6 in C&S 2016, p. 45.
 /// 
 /// >A *lexical array* (LA) is a finite set of lexical item tokens.
-#[derive(Debug, Clone, PartialEq, Eq, Deref, DerefMut)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deref, DerefMut)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LexicalArray(pub Set<LexicalItemToken>);
 
@@ -81,14 +220,14 @@ impl LexicalArray {
 /// Workspace.
 /// 
 /// See [`Stage`].
-#[derive(Debug, Clone, PartialEq, Eq, Deref, DerefMut)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deref, DerefMut)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Workspace(pub Set<SyntacticObject>);
+pub struct Workspace<S: SO = SyntacticObject>(pub Set<S>);
 
 
 
-impl Workspace {
-    pub fn new(set: Set<SyntacticObject>) -> Self {
+impl<S: SO> Workspace<S> {
+    pub fn new(set: Set<S>) -> Self {
         Workspace(set)
     }
 }
@@ -105,7 +244,7 @@ pub(crate) use w;
 
 
 
-impl Workspace {
+impl<S: SO> Workspace<S> {
     /// Immediate containment.
     /// 
     /// Derived from Definition 8, C&S 2016, p. 46.
@@ -113,7 +252,7 @@ impl Workspace {
     /// Something like:
     /// 
     /// >Let $W$ be a workspace and $A$ a syntactic object, then $W$ *immediately contains* $A$ iff $A \\in W$.
-    pub fn immediately_contains(&self, so: &SyntacticObject) -> bool {
+    pub fn immediately_contains(&self, so: &S) -> bool {
         self.0.contains(so)
     }
 
@@ -127,7 +266,7 @@ impl Workspace {
     /// >
     /// >1.  $W$ immediately contains $A$, or
     /// >2.  for some syntactic object $B$, $W$ immediately contains $B$ and $B$ contains $A$.
-    pub fn contains(&self, so: &SyntacticObject) -> bool {
+    pub fn contains(&self, so: &S) -> bool {
         self.0.contains(so) ||
         self.0.iter()
             .any(|b| b.contains(so))
@@ -136,7 +275,7 @@ impl Workspace {
 
 
     /// Return an iterator over all the syntactic objects contained in `self`.
-    pub fn contained_sos(&self, pic_compliant: bool) -> ContainedSyntacticObjects {
+    pub fn contained_sos(&self, pic_compliant: bool) -> ContainedSyntacticObjects<'_, S> {
         let stack = self.0.iter().collect();
         ContainedSyntacticObjects::new(stack, pic_compliant)
     }
@@ -144,51 +283,7 @@ impl Workspace {
 
 
 
-/// An iterator over the syntactic objects contained in a `Workspace`.
-pub struct ContainedSyntacticObjects<'a> {
-    /// The stack of syntactic objects that this iterator is supposed to visit.
-    stack: Vec<&'a SyntacticObject>,
-    pic_compliant: bool,
-}
-
-
-
-impl<'a> ContainedSyntacticObjects<'a> {
-    fn new(stack: Vec<&'a SyntacticObject>, pic_compliant: bool) -> Self {
-        Self { stack, pic_compliant }
-    }
-}
-
-
-
-impl<'a> Iterator for ContainedSyntacticObjects<'a> {
-    type Item = &'a SyntacticObject;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.stack.pop()
-            .map(|so| {
-                match so {
-                    &SyntacticObject::Set(ref children) => {
-                        for child in children {
-                            self.stack.push(child);
-                        }
-                    },
-                    &SyntacticObject::Transfer { ref so, .. } => {
-                        if !self.pic_compliant {
-                            self.stack.push(so);
-                        }
-                    },
-                    _ => (),
-                }
-
-                so
-            })
-    }
-}
-
-
-
-impl fmt::Display for Workspace {
+impl<S: SO> fmt::Display for Workspace<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{{\n")?;
         for so in self.0.iter() {
@@ -205,18 +300,128 @@ impl fmt::Display for Workspace {
 /// From Definition 10, C&S 2016, p. 46.
 /// 
 /// >A *stage* is a pair $S = \\langle \textrm{LA}, W \\rangle$, where $\\textrm{LA}$ is a lexical array and $W$ is a set of syntactic objects. We call $W$ the *workspace* of $S$.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Stage {
+pub struct Stage<S: SO = SyntacticObject> {
     pub la: LexicalArray,
-    pub w: Workspace,
+    pub w: Workspace<S>,
+}
+
+
+
+impl<S: SO> Stage<S> {
+    /// Apply the operation `op` to `self`, under the conditions of Derive-by-Select, Derive-by-Merge or Derive-by-Transfer.
+    /// 
+    /// Return the next stage on success, and the reason why `op` is not applicable on failure. Strong phases are determined by the phase heads of `ug`, and Derive-by-Transfer is applied under the transfer policy of `ug` (see [`TransferPolicy`]).
+    pub fn apply<T: Triggers>(&self, ug: &UniversalGrammar<T>, op: &Operation<S>) -> Result<Stage<S>, OperationError<S>> {
+        self.apply_labeled(op, &LabeledWorkspace::with_ug(ug, &self.w))
+    }
+
+    /// See [`Stage::apply`]. The labels and trigger features are looked up in `lw`, which must be the labeled workspace of `self`.
+    fn apply_labeled<T: Triggers>(&self, op: &Operation<S>, lw: &LabeledWorkspace<T, S>) -> Result<Stage<S>, OperationError<S>> {
+        let Stage { la, w } = self;
+
+        match op {
+            Operation::Select(lit) => select(lit.clone(), self.clone())
+                .map_err(|_| OperationError::NotInLexicalArray(lit.clone())),
+
+            Operation::Merge(a, b) => {
+                if !w.immediately_contains(a) {
+                    return Err(OperationError::NotARoot(a.clone()));
+                }
+
+                if !w.immediately_contains(b) && !lw.accessible_sos(a, false).any(|so| so == b) {
+                    return Err(OperationError::NotAccessible(b.clone()));
+                }
+
+                let ab = lw.triggered_merge(a.clone(), b.clone())
+                    .map_err(OperationError::Undefined)?;
+
+                let mut w = w.clone();
+                w.0.remove(a);
+                w.0.remove(b);
+                w.0.insert(ab);
+
+                Ok(Stage { la: la.clone(), w })
+            },
+
+            Operation::Transfer(so) | Operation::CyclicTransfer(so) => {
+                if !w.immediately_contains(so) {
+                    return Err(OperationError::NotARoot(so.clone()));
+                }
+
+                if !lw.is_strong_phase(so) {
+                    return Err(OperationError::NotAStrongPhase(so.clone()));
+                }
+
+                if let Some((phase, _)) = lw.untransferred_phase_in(so) {
+                    match lw.ug().transfer_policy {
+                        TransferPolicy::Strict =>
+                            return Err(OperationError::UntransferredPhase(phase.clone())),
+                        TransferPolicy::Permissive => (),
+                    }
+                }
+
+                let transferred = match op {
+                    Operation::Transfer(_) =>
+                        lw.transfer(so, so.clone())
+                            .map_err(OperationError::Undefined)?,
+                    _ =>
+                        lw.cyclic_transfer(so.clone())
+                            .map_err(OperationError::Undefined)?,
+                };
+
+                let mut w = w.clone();
+                w.0.remove(so);
+                w.0.insert(transferred);
+
+                Ok(Stage { la: la.clone(), w })
+            },
+        }
+    }
+
+    /// Return an iterator over every stage that is derivable from `self` in one step, together with the operation that derives it.
+    /// 
+    /// The next stages are derived by Derive-by-Select, Derive-by-Merge or Derive-by-Transfer (see [`is_derivation`]). Each next stage appears only once.
+    pub fn successors<'a, T: Triggers + 'a>(&'a self, ug: &'a UniversalGrammar<T>) -> impl Iterator<Item = (Operation<S>, Stage<S>)> + 'a {
+        let Stage { la, w } = self;
+
+        //  Select any lexical item token.
+        let selects = la.iter()
+            .map(|lit| Operation::Select(lit.clone()));
+
+        //  Merge any root A with a root or an SO accessible in A under the PIC of UG.
+        let lw = LabeledWorkspace::with_ug(ug, w);
+        let merges: Vec<_> = w.iter()
+            .flat_map(|a| {
+                lw.accessible_sos(a, false)
+                    .chain(w.iter())
+                    .map(move |b| Operation::Merge(a.clone(), b.clone()))
+            })
+            .collect();
+
+        //  Transfer or cyclic-transfer any root.
+        let transfers = w.iter()
+            .flat_map(|so| [
+                Operation::Transfer(so.clone()),
+                Operation::CyclicTransfer(so.clone()),
+            ]);
+
+        let mut seen = set!();
+        selects.chain(merges).chain(transfers)
+            .filter_map(move |op| {
+                self.apply_labeled(&op, &lw).ok()
+                    .map(|stage| (op, stage))
+            })
+            .filter(move |(_, stage)| seen.insert(stage.clone()))
+    }
 }
 
 
 
 /// >Derive-by-Select: for some $A \\in \\textrm{LA}\_i$, $\\langle \\textrm{LA}\_{i+1}, W\_{i+1} \\rangle = \\textrm{Select} ( A, \\langle \\textrm{LA}\_i, W\_i \\rangle )$.
 #[logwrap::logwrap]
-fn derive_by_select(stage1: &Stage, stage2: &Stage) -> bool {
+fn derive_by_select<S: SO>(stage1: &Stage<S>, stage2: &Stage<S>) -> Option<Operation<S>> {
     let Stage { la: la1, w: w1 } = stage1;
     let Stage { la: la2, w: w2 } = stage2;
 
@@ -234,7 +439,7 @@ fn derive_by_select(stage1: &Stage, stage2: &Stage) -> bool {
         my_info!("This pair of stages is derived by selecting:\n{}.", lit);
     }
 
-    res.is_some()
+    res.map(|lit| Operation::Select(lit.clone()))
 }
 
 
@@ -244,264 +449,385 @@ fn derive_by_select(stage1: &Stage, stage2: &Stage) -> bool {
 /// >    2.  either $A$ contains $B$ or $W\_i$ immediately contains $B$, and
 /// >    3.  $W\_{i+1} = ( W\_i - \\{ A, B \\} ) \\cup \\{ \\textrm{Merge} ( A, B ) \\}$.
 #[logwrap::logwrap]
-fn derive_by_merge<T: Triggers>(stage1: &Stage, stage2: &Stage) -> bool {
+fn derive_by_merge<T: Triggers, S: SO>(lw: &LabeledWorkspace<T, S>, stage1: &Stage<S>, stage2: &Stage<S>) -> Option<Operation<S>> {
     let Stage { la: la1, w: w1 } = stage1;
     let Stage { la: la2, w: w2 } = stage2;
 
     if la1 != la2 {
         my_debug!("The lexical arrays must be the same.");
-        return false;
+        return None;
     }
 
     if w1.0.is_empty() {
         my_debug!("The first workspace in the pair cannot be empty.");
-        return false;
+        return None;
     }
 
     /*
         Derive-by-Merge?
 
-        Derive-by-Merge is satisfied if there is a pair A, B that satisfy 3 conditions, one of which is that A in Wi. So we just search for a pair A, B where A is immediately contained in Wi and A, B satisfy the 2 other conditions. This works only if Wi is not empty, so we check that separately.
+        Rather than trying every pair A, B with A in Wi, we work backwards from Wi+1. Merge(A, B) = {A, B} is the only root in Wi+1 that may not be a root in Wi, so A and B can be read off it, and only that candidate pair needs to be checked.
     */
+
     my_debug!("Search for a possible pair A, B to form Merge(A, B)...");
-    my_debug!("Search for A. Iterate over all roots in the first workspace in the pair...");
-    w1.0.iter()
-        //  Iterate over all A, i.e., all root SOs in W1.
-        .any(|a| {
+    my_debug!("Search for Merge(A, B) among the roots in the second workspace in the pair...");
+    inc!();
+    let res = merge_candidates(lw, w2).into_iter()
+        .find(|&(a, b)| {
             my_debug!(
                 "Try A = {}",
                 SOPrefixFormatter::new(a, 8)
             );
-            inc!();
-            //  Iterate over some B, i.e. all SOs contained in A ...
-            my_debug!("Search for B. Iterate over all SOs contained by A...");
-            let res = a.contained_sos(false, true)
-                //  ... as well as all SOs immediately contained in W1.
-                .chain(w1.0.iter())
-                //  Check if the final condition match.
-                .find(|&b| {
-                    my_debug!(
-                        "Try B = {}",
-                        SOPrefixFormatter::new(b, 8)
-                    );
-                    let mut w = w1.clone();
-                    w.0.remove(a);
-                    w.0.remove(b);
-
-                    triggered_merge::<T>(a.clone(), b.clone(), w1)
-                        // .map_or_else(
-                        //     |e| {
-                        //         my_debug!("Merge failed with the following error: {}", e);
-                        //         false
-                        //     },
-                        //     move |ab| {
-                        .map_or(false, move |ab| {
-                                my_debug!("Merge(A, B) works.");
-                                my_debug!(
-                                    "Merge(A, B) = {}",
-                                    SOPrefixFormatter::new(&ab, 14)
-                                );
-                                w.0.insert(ab);
-                                w2 == &w
-                            }
-                        )
-                });
-            dec!();
-
-            if let Some(b) = res {
-                my_info!("This pair of stages is derived by Merge(A, B),");
-                my_info!("where A = {}", SOPrefixFormatter::new(&a, 10));
-                my_info!("  and B = {}", SOPrefixFormatter::new(b, 10));
-            }
+            my_debug!(
+                "and B = {}",
+                SOPrefixFormatter::new(b, 8)
+            );
+            lw.triggered_merge(a.clone(), b.clone()).is_ok()
+        });
+    dec!();
+
+    if let Some((a, b)) = res {
+        my_info!("This pair of stages is derived by {} Merge(A, B),", MergeKind::of(a, b));
+        my_info!("where A = {}", SOPrefixFormatter::new(a, 10));
+        my_info!("  and B = {}", SOPrefixFormatter::new(b, 10));
+    }
+
+    res.map(|(a, b)| Operation::Merge(a.clone(), b.clone()))
+}
+
+
 
-            res.is_some()
+/// Return the pairs $A, B$ that satisfy the conditions of Derive-by-Merge for the workspaces `w1` and `w2`, if Merge(A, B) is taken to be $\{ A, B \}$ whether or not it is defined.
+///
+/// Since $W\_{i+1} = ( W\_i - \{ A, B \} ) \cup \{ \{ A, B \} \}$, the set $\{ A, B \}$ is the single root in $W\_{i+1}$ that is not in $W\_i$, or, if there is no such root, one that already is. So only the members of that root are tried as $A$ and $B$, and only the two resulting pairs are checked against the conditions.
+///
+/// If A contains B, B must be accessible in A under the PIC of UG (see [`PicPolicy`]).
+fn merge_candidates<'a, T: Triggers, S: SO>(lw: &LabeledWorkspace<T, S>, w2: &'a Workspace<S>) -> Vec<(&'a S, &'a S)> {
+    let w1 = lw.workspace();
+    let new_roots: Vec<&S> = w2.iter()
+        .filter(|&so| !w1.0.contains(so))
+        .collect();
+
+    let roots = match new_roots.len() {
+        0 => w2.iter().collect(),
+        1 => new_roots,
+        _ => vec![],
+    };
+
+    roots.into_iter()
+        .filter_map(|ab| {
+            match ab.view() {
+                SOView::Set(set) if set.len() == 2 => {
+                    let mut members = set.iter();
+                    Some((members.next()?, members.next()?))
+                },
+                _ => None,
+            }
+        })
+        .flat_map(|(p, q)| vec![ (p, q), (q, p) ])
+        //  A is a root in W1, and either A contains B or B is a root in W1.
+        .filter(|&(a, b)| {
+            w1.0.contains(a) &&
+            (
+                w1.0.contains(b) ||
+                lw.accessible_sos(a, false).any(|so| so == b)
+            )
         })
+        //  W2 = (W1 - {A, B}) U {{A, B}}.
+        .filter(|&(a, b)| {
+            let mut w = w1.clone();
+            w.0.remove(a);
+            w.0.remove(b);
+            w.0.insert(S::from_set(set!( a.clone(), b.clone() )));
+            &w == w2
+        })
+        .collect()
 }
 
 
 
-/// >Derive-by-Merge: $\\textrm{LA}\_i = \\textrm{LA}\_{i+1}$ and the following conditions hold for some $A, B$:
-/// >    1.  $A \\in W\_i$,
-/// >    2.  either $A$ contains $B$ or $W\_i$ immediately contains $B$, and
-/// >    3.  $W\_{i+1} = ( W\_i - \\{ A, B \\} ) \\cup \\{ \\textrm{Merge} ( A, B ) \\}$.
+/// >Derive-by-Transfer: $\\textrm{LA}\_i = \\textrm{LA}\_{i+1}$ and there is some $SO \\in W\_i$ such that $SO$ is a strong phase, containing no other strong phase whose complement has not been transferred, and either:
+/// >    *   $W\_{i+1} = ( W\_i - \\{ SO \\} ) \\cup \\{ \\textrm{Transfer} ( SO, SO ) \\}$, or
+/// >    *   $W\_{i+1} = ( W\_i - \\{ SO \\} ) \\cup \\{ \\textrm{Cyclic-Transfer} ( SO ) \\}$.
+///
+/// Under [`TransferPolicy::Permissive`], $SO$ may also contain a strong phase whose complement has not been transferred.
 #[logwrap::logwrap]
-fn derive_by_transfer<T: Triggers>(stage1: &Stage, stage2: &Stage) -> bool {
+fn derive_by_transfer<T: Triggers, S: SO>(lw: &LabeledWorkspace<T, S>, stage1: &Stage<S>, stage2: &Stage<S>) -> Option<Operation<S>> {
     let Stage { la: la1, w: w1 } = stage1;
     let Stage { la: la2, w: w2 } = stage2;
 
     if la1 != la2 {
         my_debug!("The lexical arrays must be the same.");
-        return false;
+        return None;
     }
 
     if w1.0.is_empty() {
         my_debug!("The first workspace in the pair cannot be empty.");
-        return false;
+        return None;
     }
 
     my_debug!("Search for a strong phase...");
     w1.0.iter()
-        .any(|so1| {
+        .find_map(|so1| {
             //  Is a strong phase...
-            is_strong_phase::<T>(so1, w1) &&
-            //  Containing no other...
-            {
+            if !lw.is_strong_phase(so1) {
+                return None;
+            }
+
+            //  Containing no other strong phase whose complement has not been transferred...
+            my_debug!(
+                "SO1 is a strong phase: {}",
+                SOPrefixFormatter::new(so1, 23)
+            );
+
+            if let Some((so2, so4)) = lw.untransferred_phase_in(so1) {
+                my_debug!(
+                    "SO1 contains a strong phase SO2 = {}",
+                    SOPrefixFormatter::new(so2, 34)
+                );
                 my_debug!(
-                    "SO1 is a strong phase: {}",
-                    SOPrefixFormatter::new(so1, 23)
+                    "whose complement SO4, shown below, is not transferred:\n{}",
+                    so4
                 );
 
-                // !so1.contained_sos(false, true)
-                //     .any(|so2| {
-                //         //  Strong phases...
-                //         is_strong_phase::<T>(so2, w1) &&
-                //         //  whose...
-                //         match T::label_of(so2, w1) {
-                //             Ok(lit) => {
-                //                 let head = so!(lit.clone());
-
-                //                 so2.contained_sos(true, true)
-                //                     .any(|so3| {
-                //                         match so3 {
-                //                             &SyntacticObject::Set(ref vec) => {
-                //                                 vec.iter()
-                //                                     .any(|so4| {
-                //                                         //  complement...
-                //                                         so4.is_complement_of::<T>(&head, so3, w1) &&
-                //                                         //  has not yet been transferred...
-                //                                         match so4 {
-                //                                             &SyntacticObject::Transfer { .. } => false,
-                //                                             _ => true,
-                //                                         }
-                //                                     })
-                //                             },
-                //                             _ => false,
-                //                         }
-                //                     })
-                //             },
-                //             _ => false,
-                //         }
-                //     })
-
-                let exception: Option<(&SyntacticObject, &SyntacticObject)> =
-                    so1.contained_sos(false, true)
-                        //  Look for a strong phase SO2.
-                        .filter(|&so2| {
-                            is_strong_phase::<T>(so2, w1)
-                        })
-                        //  Find the head of each SO2.
-                        .filter_map(|so2| {
-                            match T::label_of(so2, w1) {
-                                Ok(label) => {
-                                    Some((so2, label))
-                                },
-                                _ => None,
-                            }
-                        })
-                        //  Iterate over each child SO3 of SO2.
-                        .map(|(so2, label)| {
-                            let so3s = so2.contained_sos(true, true);
-                            (so2, label, so3s)
-                        })
-                        //  If SO3 = { ... }, iterate over each child SO4 of
-                        //  SO3 such that SO4 is the complement of SO3.
-                        .map(|(so2, label, so3s)| {
-                            let so4s = so3s
-                                .filter_map(move |so3| {
-                                    match so3 {
-                                        &SyntacticObject::Set(ref vec) => {
-                                            Some(vec.iter()
-                                                .filter(move |&so4| {
-                                                    let head = so!(label.clone());
-                                                    so4.is_complement_of::<T>(&head, so3, w1)
-                                                }))
-                                        },
-                                        _ => None,
-                                    }
-                                })
-                                .flatten();
-                            (so2, so4s)
-                        })
-                        //  We have an exception if SO4 is not yet transferred.
-                        .map(|(so2, so4s)| {
-                            (so2, so4s.filter(|so4| {
-                                match so4 {
-                                    &SyntacticObject::Transfer { .. } => false,
-                                    _ => true,
-                                }
-                            }))
-                        })
-                        //  Flatten!
-                        .map(|(so2, mut so4s)| {
-                            (so2, so4s.next())
-                        })
-                        .filter(|(so2, so4)| {
-                            so4.is_some()
-                        })
-                        .map(|(so2, so4)| {
-                            (so2, so4.unwrap())
-                        })
-                        .next();
-
-                if let Some((so2, so4)) = exception {
-                    my_debug!(
-                        "SO1 contains a strong phase SO2 = {}",
-                        SOPrefixFormatter::new(so2, 34)
-                    );
-                    my_debug!(
-                        "whose complement SO4, shown below, is not transferred:\n{}",
-                        so4
-                    );
-                }
-                else {
-                    my_debug!("SO1 does not contain a strong phase whose complement has not yet been transferred.");
+                match lw.ug().transfer_policy {
+                    TransferPolicy::Strict => return None,
+                    TransferPolicy::Permissive => {
+                        my_debug!("Ignore this, since the transfer policy is permissive.");
+                    },
                 }
+            }
+            else {
+                my_debug!("SO1 does not contain a strong phase whose complement has not yet been transferred.");
+            }
 
-                true
-            } &&
             //  And either...
-            ({
+            {
                 // eprintln!("Derivation: Try Transfer ::::::::::::::::::::::::::::::::");
                 my_debug!("Try Transfer(SO1, SO1)...");
                 let mut w = w1.clone();
                 w.0.remove(so1);
-                w.0.insert(transfer::<T>(&so1, so1.clone(), w1));
-                my_debug!("The workspace should be: {}", w);
-                
-                let res = w == *w2;
+                match lw.transfer(so1, so1.clone()) {
+                    Ok(so2) => {
+                        w.0.insert(so2);
+                        my_debug!("The workspace should be: {}", w);
+
+                        let res = w == *w2;
 
-                if res {
-                    my_info!("This pair of stages is derived by Transfer(SO1, SO1).");
+                        if res {
+                            my_info!("This pair of stages is derived by Transfer(SO1, SO1).");
+                            return Some(Operation::Transfer(so1.clone()));
+                        }
+                    },
+                    Err(e) => {
+                        my_debug!("Transfer failed: {}", e);
+                    },
                 }
+            }
 
-                res
-            } || {
+            {
                 // eprintln!("Derivation: Try Cyclic-Transfer :::::::::::::::::::::::::");
                 my_debug!("Try Cyclic-Transfer(SO1)...");
                 let mut w = w1.clone();
                 w.0.remove(so1);
-                match cyclic_transfer::<T>(so1.clone(), w1) {
+                match lw.cyclic_transfer(so1.clone()) {
                     Ok(so2) => {
                         w.0.insert(so2);
                         my_debug!("The workspace should be: {}", w);
-                        
+
                         let res = w == *w2;
 
                         if res {
                             my_info!("This pair of stages is derived by Cyclic-Transfer(SO1).");
+                            return Some(Operation::CyclicTransfer(so1.clone()));
                         }
-
-                        res
                     },
-                    Err(_) => false,
+                    Err(e) => {
+                        my_debug!("Cyclic-Transfer failed: {}", e);
+                    },
                 }
-            })
+            }
+
+            None
         })
 }
 
 
 
-/// Check if the sequence of stages `stages` is a derivation from the I-language `il`.
+/// Find out why the pair of stages `stage1`, `stage2` is not derived by Merge, if only the trigger features are to blame.
+///
+/// That is, if $W\\_{i+1} = ( W\\_i - \\{ A, B \\} ) \\cup \\{ \\{ A, B \\} \\}$ for some $A, B$ as in Derive-by-Merge, return the reason why Merge(A, B) is undefined.
+fn explain_merge_failure<T: Triggers, S: SO>(lw: &LabeledWorkspace<T, S>, stage1: &Stage<S>, stage2: &Stage<S>) -> Option<CandsError<S>> {
+    let Stage { la: la1, .. } = stage1;
+    let Stage { la: la2, w: w2 } = stage2;
+
+    if la1 != la2 {
+        return None;
+    }
+
+    let reason = merge_candidates(lw, w2).into_iter()
+        .find_map(|(a, b)| lw.triggered_merge(a.clone(), b.clone()).err());
+    reason
+}
+
+
+
+/// One step of a derivation, i.e. the operation that derives stage `stage + 1` from stage `stage`.
+///
+/// Stages are numbered from 1, as in C&S 2016.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationStep<S: SO = SyntacticObject> {
+    pub stage: usize,
+    pub op: Operation<S>,
+    /// Whether `op` is internal or external Merge, if it is Merge at all.
+    pub merge: Option<MergeKind>,
+}
+
+
+
+impl<S: SO> fmt::Display for DerivationStep<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}): ", self.stage, self.stage + 1)?;
+        if let Some(merge) = self.merge {
+            write!(f, "{} ", merge)?;
+        }
+        write!(f, "{}", self.op)
+    }
+}
+
+
+
+/// The result of a successful derivation check.
+///
+/// For every pair of consecutive stages, this records the operation that licenses it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationReport<S: SO = SyntacticObject> {
+    pub steps: Vec<DerivationStep<S>>,
+}
+
+
+
+impl<S: SO> DerivationReport<S> {
+    /// Count the steps that are Merge of the kind `kind`, e.g. the movement steps if `kind` is [`MergeKind::Internal`].
+    pub fn count_merges(&self, kind: MergeKind) -> usize {
+        self.steps.iter()
+            .filter(|step| step.merge == Some(kind))
+            .count()
+    }
+}
+
+
+
+impl<S: SO> fmt::Display for DerivationReport<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in self.steps.iter() {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+/// The reason why a sequence of stages is not a derivation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivationError<S: SO = SyntacticObject> {
+    /// The derivation has no stages.
+    NoStages,
+    /// Some lexical items in the lexicon use features that UG does not declare.
+    InvalidLexicon(Vec<UndeclaredFeature>),
+    /// A lexical item token in the first lexical array is based on a lexical item that is not in the lexicon.
+    NotInLexicon(LexicalItemToken),
+    /// Another lexical item token in the first lexical array has the same lexical item (up to shorthands) and the same index as this one.
+    SharedTokenIndex(LexicalItemToken),
+    /// The workspace of the first stage is not empty.
+    NonEmptyFirstWorkspace,
+    /// A lexical item token at stage `stage` is not in the first lexical array.
+    UnknownToken { stage: usize, lit: LexicalItemToken },
+    /// Stage `stage + 1` is not derivable from stage `stage` by Select, Merge or Transfer.
+    ///
+    /// If it would be derivable by Merge(A, B) if it were not for the trigger features of A and B, `reason` says why Merge(A, B) is undefined.
+    NoMatchingRule { stage: usize, reason: Option<CandsError<S>> },
+    /// Stage `stage + 1` is not derivable from stage `stage` in at most `bound` steps.
+    Unreachable { stage: usize, bound: usize },
+}
+
+
+
+impl<S: SO> fmt::Display for DerivationError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DerivationError::NoStages =>
+                write!(f, "The derivation must have >= 1 stages."),
+            DerivationError::InvalidLexicon(undeclared) => {
+                write!(f, "The lexicon is not valid with respect to UG.")?;
+                undeclared.iter()
+                    .try_for_each(|u| write!(f, "\n  {}", u))
+            },
+            DerivationError::NotInLexicon(lit) =>
+                write!(f, "Can't find the lexical item of this lexical item token in the lexicon: {}", lit),
+            DerivationError::SharedTokenIndex(lit) =>
+                write!(f, "Another lexical item token of the same lexical item in the first lexical array has the same index as this lexical item token: {}", lit),
+            DerivationError::NonEmptyFirstWorkspace =>
+                write!(f, "The first workspace must be empty."),
+            DerivationError::UnknownToken { stage, lit } =>
+                write!(f, "Stage {} has a lexical item token that is not in the first lexical array: {}", stage, lit),
+            DerivationError::NoMatchingRule { stage, reason } => {
+                write!(f, "The pair of stages ({}, {}) is not derived by Select, Merge or Transfer.", stage, stage + 1)?;
+                if let Some(reason) = reason {
+                    write!(f, "\nIt would be derived by Merge, but: {}", reason)?;
+                }
+                Ok(())
+            },
+            DerivationError::Unreachable { stage, bound } =>
+                write!(f, "Stage {} is not derivable from stage {} in at most {} steps.", stage + 1, stage, bound),
+        }
+    }
+}
+
+
+
+/// The reason why an operation is not applicable to a stage (see [`Stage::apply`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OperationError<S: SO = SyntacticObject> {
+    /// Select(A) is not applicable, because A, i.e. this lexical item token, is not in the lexical array.
+    NotInLexicalArray(LexicalItemToken),
+    /// A in Merge(A, B), or the syntactic object to transfer, i.e. this syntactic object, is not a root in the workspace.
+    NotARoot(S),
+    /// B in Merge(A, B), i.e. this syntactic object, is neither a root in the workspace nor accessible in A.
+    NotAccessible(S),
+    /// This syntactic object cannot be transferred, because it is not a strong phase.
+    NotAStrongPhase(S),
+    /// The syntactic object to transfer contains this strong phase, whose complement has not been transferred yet (see [`TransferPolicy::Strict`]).
+    UntransferredPhase(S),
+    /// The operation itself is undefined.
+    Undefined(CandsError<S>),
+}
+
+
+
+impl<S: SO> fmt::Display for OperationError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationError::NotInLexicalArray(lit) =>
+                write!(f, "This lexical item token is not in the lexical array: {}", lit),
+            OperationError::NotARoot(so) =>
+                write!(f, "This syntactic object is not a root in the workspace: {}", SOPrefixFormatter::new(so, 54)),
+            OperationError::NotAccessible(so) =>
+                write!(f, "This syntactic object is neither a root in the workspace nor accessible in A: {}", SOPrefixFormatter::new(so, 78)),
+            OperationError::NotAStrongPhase(so) =>
+                write!(f, "This syntactic object is not a strong phase: {}", SOPrefixFormatter::new(so, 45)),
+            OperationError::UntransferredPhase(so) =>
+                write!(f, "The complement of the head of this strong phase has not been transferred: {}", SOPrefixFormatter::new(so, 74)),
+            OperationError::Undefined(e) =>
+                write!(f, "{}", e),
+        }
+    }
+}
+
+
+
+/// Check if the sequence of stages `stages` is a derivation from the I-language `il`, and report how each stage is derived.
 /// 
 /// From Definition 14, C&S 2016, p. 48. The original derivation, given below, defines a derivation with respect to just a lexicon, but since it invokes syntactic operations like Select and Merge, we define it with respect to an I-language, which includes a UG as well as a lexicon.
 /// 
@@ -514,12 +840,172 @@ fn derive_by_transfer<T: Triggers>(stage1: &Stage, stage2: &Stage) -> bool {
 /// >    *   Derive-by-Merge, or
 /// >    *   Derive-by-Transfer.
 #[logwrap::logwrap]
-pub fn is_derivation<T: Triggers>(il: &ILanguage<T>, stages: &[Stage]) -> bool {
+pub fn check_derivation<T: Triggers, S: SO>(il: &ILanguage<T>, stages: &[Stage<S>]) -> Result<DerivationReport<S>, DerivationError<S>> {
+    check_first_stage(il, stages)?;
+
+    check_tokens(stages)?;
+
+    //  Check every stage.
+    my_debug!("Step 6: Check if every (non-first) stage is derivable from the previous stage...");
+
+    let steps = stages.windows(2)
+        .enumerate()
+        .map(|(stage_idx, stage_pair)| check_stage_pair::<T, S>(&il.ug, stage_idx + 1, &stage_pair[0], &stage_pair[1]))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(DerivationReport { steps })
+}
+
+
+
+/// Check if the stage `stage2` is derived from the stage `stage1`, which is stage `fst_stage_idx` of a derivation, and report how.
+///
+/// This is step 6 of [`check_derivation`] for a single pair of stages. It does not depend on the other stages in the derivation.
+fn check_stage_pair<T: Triggers, S: SO>(ug: &UniversalGrammar<T>, fst_stage_idx: usize, stage1: &Stage<S>, stage2: &Stage<S>) -> Result<DerivationStep<S>, DerivationError<S>> {
+    let snd_stage_idx = fst_stage_idx + 1;
+
+    //  Report the current pair of stages
+    my_debug!("============================================================");
+    my_debug!("Checking the pair ({}, {})...", fst_stage_idx, snd_stage_idx);
+
+    my_debug!("------------------------------------------------------------");
+    my_debug!(
+        "Lexical array {}: {{{}\n}}",
+        fst_stage_idx,
+        stage1.la.iter()
+            .map(|lit| format!("  {},", lit))
+            .fold(String::new(), |a, b| format!("{}\n{}", a, b))
+    );
+    my_debug!(
+        "Workspace {}: {}",
+        fst_stage_idx,
+        stage1.w
+    );
+
+    my_debug!("------------------------------------------------------------");
+    my_debug!(
+        "Lexical array {}: {{{}\n}}",
+        snd_stage_idx,
+        stage2.la.iter()
+            .map(|lit| format!("  {},", lit))
+            .fold(String::new(), |a, b| format!("{}\n{}", a, b))
+    );
+    my_debug!(
+        "Workspace {}: {}",
+        snd_stage_idx,
+        stage2.w
+    );
+
+    //  Label the first workspace once, for all the rules
+    let lw = LabeledWorkspace::with_ug(ug, &stage1.w);
+
+    //  Derive-by-Select?
+    my_debug!("------------------------------------------------------------");
+    my_debug!("Check for Derive-by-Select...");
+    let op = derive_by_select(stage1, stage2)
+        .or_else(|| {
+            my_debug!("No match.");
+
+            //  Derive-by-Merge?
+            my_debug!("------------------------------------------------------------");
+            my_debug!("Check for Derive-by-Merge...");
+            derive_by_merge::<T, S>(&lw, stage1, stage2)
+        })
+        .or_else(|| {
+            my_debug!("No match.");
+
+            //  Derive-by-Transfer?
+            my_debug!("------------------------------------------------------------");
+            my_debug!("Check for Derive-by-Transfer...");
+            derive_by_transfer::<T, S>(&lw, stage1, stage2)
+        });
+
+    match op {
+        Some(op) => {
+            my_debug!("Match!");
+            let merge = match &op {
+                Operation::Merge(a, b) => Some(MergeKind::of(a, b)),
+                _ => None,
+            };
+            Ok(DerivationStep { stage: fst_stage_idx, op, merge })
+        },
+        None => {
+            my_debug!("No match.");
+            let reason = explain_merge_failure::<T, S>(&lw, stage1, stage2);
+            Err(DerivationError::NoMatchingRule { stage: fst_stage_idx, reason })
+        },
+    }
+}
+
+
+
+/// Like [`check_derivation`], but check the pairs of stages on up to `threads` threads at once.
+///
+/// Each pair of stages is checked independently of the others, so the result is the same as that of [`check_derivation`]. In particular, if more than one pair fails the check, the error is about the first of them. Once a pair fails, pairs after it are no longer checked.
+pub fn check_derivation_parallel<T: Triggers + Sync, S: SO + Send + Sync>(il: &ILanguage<T>, stages: &[Stage<S>], threads: usize) -> Result<DerivationReport<S>, DerivationError<S>> {
+    check_first_stage(il, stages)?;
+
+    check_tokens(stages)?;
+
+    my_debug!("Step 6: Check if every (non-first) stage is derivable from the previous stage, on up to {} threads...", threads);
+
+    let pairs: Vec<&[Stage<S>]> = stages.windows(2).collect();
+    //  The index of the next pair to check, and the index of the first pair known to fail the check.
+    let next = AtomicUsize::new(0);
+    let first_failure = AtomicUsize::new(pairs.len());
+
+    let mut results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads.clamp(1, pairs.len().max(1)))
+            .map(|_| scope.spawn(|| {
+                let mut results = vec![];
+                loop {
+                    let idx = next.fetch_add(1, AtomicOrdering::Relaxed);
+                    if idx >= first_failure.load(AtomicOrdering::Relaxed) {
+                        break;
+                    }
+
+                    let res = check_stage_pair::<T, S>(&il.ug, idx + 1, &pairs[idx][0], &pairs[idx][1]);
+                    if res.is_err() {
+                        first_failure.fetch_min(idx, AtomicOrdering::Relaxed);
+                    }
+                    results.push((idx, res));
+                }
+                results
+            }))
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    //  Report the first failure in stage order.
+    results.sort_by_key(|(idx, _)| *idx);
+    let steps = results.into_iter()
+        .map(|(_, res)| res)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(DerivationReport { steps })
+}
+
+
+
+/// Check the conditions on the first stage of the derivation `stages`, i.e. conditions 1 and 2 of Definition 14 (see [`check_derivation`]), as well as the validity of the lexicon and of the indices of the lexical item tokens in the first lexical array.
+pub(crate) fn check_first_stage<T: Triggers, S: SO>(il: &ILanguage<T>, stages: &[Stage<S>]) -> Result<(), DerivationError<S>> {
+    //  The lexicon itself must be valid.
+    my_debug!("Step 0: Check if every lexical item in the lexicon only uses features declared in UG...");
+    il.validate()
+        .map_err(DerivationError::InvalidLexicon)?;
+
+    //  Likely mistakes in the trigger features do not make the derivation invalid, but are reported.
+    for warning in il.ug.trigger_warnings() {
+        my_warn!("{}", warning);
+    }
+
     //  A derivation must have positive length.
     my_debug!("Step 1: Check if the derivation has positive length, i.e. that it has a positive number of stages...");
-    if stages.len() < 1 {
-        my_info!("The derivation must have >= 1 stages.");
-        return false;
+    if stages.is_empty() {
+        return Err(DerivationError::NoStages);
     }
 
 
@@ -527,127 +1013,88 @@ pub fn is_derivation<T: Triggers>(il: &ILanguage<T>, stages: &[Stage]) -> bool {
     //  Check if all lexical item tokens at the first stage are in the lexicon.
     my_debug!("Step 2: Check if all the lexical item tokens in the lexical array of the first stage are in the lexicon...");
     
-    let stage1 = &stages[0];
-    let Stage { la: la1, w: w1 } = stage1;
+    let Stage { la: la1, w: w1 } = &stages[0];
+    let ILanguage { lex, .. } = il;
 
-    let ILanguage { lex, ug, .. } = il;
+    if let Some(lit) = la1.0.iter().find(|lit| !lex.contains(&lit.li)) {
+        return Err(DerivationError::NotInLexicon(lit.clone()));
+    }
 
-    for lit in la1.0.iter() {
-        let LexicalItemToken { li, .. } = lit;
-        if !lex.contains(li) {
-            my_info!("Can't find this lexical item in the lexicon: {}", li);
-            return false;
-        }
+    //  Check if lexical item tokens of the same lexical item at the first stage have distinct indices.
+    //  Since the lexical array is a set, two such tokens can only have the same index if their lexical items differ in their shorthands.
+    my_debug!("Step 3: Check if the lexical item tokens of each lexical item in the lexical array of the first stage have distinct indices...");
+
+    if let Some(lit) = la1.0.iter()
+        .tuple_combinations()
+        .find(|(lit1, lit2)| (lit1.k == lit2.k) && lit1.li.has_same_features_as(&lit2.li))
+        .map(|(_, lit2)| lit2)
+    {
+        return Err(DerivationError::SharedTokenIndex(lit.clone()));
     }
 
     //  Check if the workspace at the first stage is empty.
-    my_debug!("Step 3: Check if the workspace of the first stage is empty...");
+    my_debug!("Step 4: Check if the workspace of the first stage is empty...");
 
     if !w1.0.is_empty() {
-        eprintln!("The first workspace must be empty.");
-        // return false;
-        return false;
+        return Err(DerivationError::NonEmptyFirstWorkspace);
     }
 
-    //  Check every stage.
-    my_debug!("Step 4: Check if every (non-first) stage is derivable from the previous stage...");
-
-    for (stage_idx, stage_pair) in stages.windows(2).enumerate() {
-        let fst_stage_idx = stage_idx + 1;
-        let snd_stage_idx = stage_idx + 2;
-
-        //  Report the current pair of stages
-        my_debug!("============================================================");
-        my_debug!("Checking the pair ({}, {})...", fst_stage_idx, snd_stage_idx);
-
-        let stage1 = &stage_pair[0];
-        let stage2 = &stage_pair[1];
-
-        let Stage { la: la1, w: w1 } = stage1;
-        let Stage { la: la2, w: w2 } = stage2;
-
-        my_debug!("------------------------------------------------------------");
-        my_debug!(
-            "Lexical array {}: {{{}\n}}",
-            fst_stage_idx,
-            stage1.la.iter()
-                .map(|lit| format!("  {},", lit))
-                .fold(format!(""), |a, b| format!("{}\n{}", a, b))
-        );
-        my_debug!(
-            "Workspace {}: {}",
-            fst_stage_idx,
-            stage1.w
-        );
-
-        my_debug!("------------------------------------------------------------");
-        my_debug!(
-            "Lexical array {}: {{{}\n}}",
-            snd_stage_idx,
-            stage2.la.iter()
-                .map(|lit| format!("  {},", lit))
-                .fold(format!(""), |a, b| format!("{}\n{}", a, b))
-        );
-        my_debug!(
-            "Workspace {}: {}",
-            snd_stage_idx,
-            stage2.w
-        );
-
-        let step_ok = loop {
-            //  Derive-by-Select?
-            my_debug!("------------------------------------------------------------");
-            my_debug!("Check for Derive-by-Select...");
-            if derive_by_select(stage1, stage2) {
-                my_debug!("Match!");
-                break true;
-            }
-            my_debug!("No match.");
+    Ok(())
+}
 
-            //  Derive-by-Merge?
 
-            my_debug!("------------------------------------------------------------");
-            my_debug!("Check for Derive-by-Merge...");
-            if derive_by_merge::<T>(stage1, stage2) {
-                my_debug!("Match!");
-                break true;
-            }
-            my_debug!("No match.");
 
-            //  Derive-by-Transfer?
-            my_debug!("------------------------------------------------------------");
-            my_debug!("Check for Derive-by-Transfer...");
-            if derive_by_transfer::<T>(stage1, stage2) {
-                my_debug!("Match!");
-                break true;
-            }
-            my_debug!("No match.");
+/// Check if every lexical item token at every stage of the derivation `stages` comes from the first lexical array.
+///
+/// Lexical item tokens are never created during a derivation; Select only moves them from the lexical array to the workspace.
+fn check_tokens<S: SO>(stages: &[Stage<S>]) -> Result<(), DerivationError<S>> {
+    my_debug!("Step 5: Check if every lexical item token at every stage is in the lexical array of the first stage...");
 
-            break false;
-        };
-
-        if !step_ok {
-            eprintln!("This pair of stages is invalid:");
-            eprintln!("Stage 1:\n");
-            eprintln!(
-                " :: Lexical array: {{{}\n}}",
-                stage1.la.iter()
-                    .map(|lit| format!("{}", lit))
-                    .fold(format!(""), |a, b| format!("{}\n{}", a, b))
-            );
-            eprintln!(" :: Workspace:\n{}", stage1.w);
-            eprintln!("Stage 2:\n");
-            eprintln!(
-                " :: Lexical array: {{{}\n}}",
-                stage2.la.iter()
-                    .map(|lit| format!("{}", lit))
-                    .fold(format!(""), |a, b| format!("{}\n{}", a, b))
-            );
-            eprintln!(" :: Workspace:\n{}", stage2.w);
-            // return false;
-            return false;
+    let la1 = &stages[0].la;
+
+    for (stage_idx, Stage { la, w }) in stages.iter().enumerate() {
+        let unknown = la.iter()
+            .chain(
+                w.contained_sos(false)
+                    .filter_map(|so| match so.view() {
+                        SOView::LexicalItemToken(lit) => Some(lit),
+                        _ => None,
+                    })
+            )
+            .find(|lit| !la1.contains(lit));
+
+        if let Some(lit) = unknown {
+            return Err(DerivationError::UnknownToken { stage: stage_idx + 1, lit: lit.clone() });
         }
     }
 
-    true
-}
\ No newline at end of file
+    Ok(())
+}
+
+
+
+/// Check if the sequence of stages `stages` is a derivation from the I-language `il`.
+/// 
+/// See [`check_derivation`], which also reports how each stage is derived, or why the check failed.
+pub fn is_derivation<T: Triggers, S: SO>(il: &ILanguage<T>, stages: &[Stage<S>]) -> bool {
+    match check_derivation(il, stages) {
+        Ok(_) => true,
+        Err(e) => {
+            my_info!("{}", e);
+            false
+        },
+    }
+}
+
+
+
+/// Like [`is_derivation`], but check the pairs of stages on up to `threads` threads at once. See [`check_derivation_parallel`].
+pub fn is_derivation_parallel<T: Triggers + Sync, S: SO + Send + Sync>(il: &ILanguage<T>, stages: &[Stage<S>], threads: usize) -> bool {
+    match check_derivation_parallel(il, stages, threads) {
+        Ok(_) => true,
+        Err(e) => {
+            my_info!("{}", e);
+            false
+        },
+    }
+}
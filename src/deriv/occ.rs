@@ -0,0 +1,296 @@
+use crate::prelude::*;
+
+use derive_more::{ Deref, DerefMut };
+
+use std::fmt;
+
+
+
+/// Path.
+///
+/// From Definition 16, C&S 2016, p. 50.
+///
+/// Something like:
+///
+/// >A *path* is a sequence of syntactic objects $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n \\rangle$ ($n \\geq 1$), such that for all $i$, $1 \\leq i < n$, $\\textrm{SO}\_{i+1} \\in \\textrm{SO}\_i$. If $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n \\rangle$ is a path, it is a *position* of $\\textrm{SO}\_n$ in $\\textrm{SO}\_1$.
+///
+/// See [`SyntacticObject::paths_to`] for how to get every position of a syntactic object.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deref, DerefMut)]
+pub struct Path<'a>(pub Vec<&'a SyntacticObject>);
+
+
+
+impl<'a> Path<'a> {
+    pub fn new(sos: Vec<&'a SyntacticObject>) -> Self {
+        Path(sos)
+    }
+
+    /// Check if `self` is a path, i.e. that it is not empty and that each syntactic object in it is immediately contained in the previous one.
+    pub fn is_path(&self) -> bool {
+        !self.is_empty() &&
+        self.windows(2)
+            .all(|pair| pair[0].immediately_contains(pair[1]))
+    }
+
+    /// The first syntactic object $\\textrm{SO}\_1$ in the path.
+    pub fn root(&self) -> &'a SyntacticObject {
+        self.first().unwrap()
+    }
+
+    /// The last syntactic object $\\textrm{SO}\_n$ in the path, i.e. the syntactic object that the path is a position of.
+    pub fn end(&self) -> &'a SyntacticObject {
+        self.last().unwrap()
+    }
+
+    /// Check if `self` is a (not necessarily proper) prefix of `other`.
+    pub fn is_prefix_of(&self, other: &Path) -> bool {
+        other.starts_with(self)
+    }
+
+    /// Return the path that extends `self` by `so`.
+    pub fn extended_by(&self, so: &'a SyntacticObject) -> Self {
+        let mut path = self.clone();
+        path.push(so);
+        path
+    }
+}
+
+
+
+impl<'a> fmt::Display for Path<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<")?;
+        for (i, so) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", so)?;
+        }
+        write!(f, ">")
+    }
+}
+
+
+
+/// Occurrence.
+///
+/// From Definition 17, C&S 2016, p. 51.
+///
+/// Something like:
+///
+/// >An *occurrence* of $A$ in $B$ is a position of $A$ in $B$.
+///
+/// Because a syntactic object that undergoes internal Merge is contained in the same root more than once, it can have more than one occurrence in that root. Occurrences tell these copies apart. See [`SyntacticObject::occurrences_of`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Occurrence<'a> {
+    /// The position $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n \\rangle$ of this occurrence, from the root $\\textrm{SO}\_1$ down to the occurring syntactic object $\\textrm{SO}\_n$.
+    path: Path<'a>,
+}
+
+
+
+impl<'a> Occurrence<'a> {
+    pub fn new(path: Path<'a>) -> Self {
+        Self { path }
+    }
+
+    /// Check if the position of `self` is a path.
+    pub fn check(&self) -> bool {
+        self.path.is_path()
+    }
+
+    /// The position of this occurrence.
+    pub fn path(&self) -> &Path<'a> {
+        &self.path
+    }
+
+    /// The syntactic object this is an occurrence of.
+    pub fn so(&self) -> &'a SyntacticObject {
+        self.path.end()
+    }
+
+    /// The syntactic object this is an occurrence in.
+    pub fn root(&self) -> &'a SyntacticObject {
+        self.path.root()
+    }
+
+    /// Immediate containment.
+    ///
+    /// From Definition 18, C&S 2016, p. 51.
+    ///
+    /// Something like:
+    ///
+    /// >Occurrence $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n \\rangle$ *immediately contains* occurrence $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n, \\textrm{SO}\_{n+1} \\rangle$.
+    pub fn immediately_contains(&self, other: &Occurrence) -> bool {
+        (other.path.len() == self.path.len() + 1) &&
+        self.path.is_prefix_of(&other.path)
+    }
+
+    /// Containment.
+    ///
+    /// The transitive closure of [`Occurrence::immediately_contains`], i.e. occurrence $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n \\rangle$ *contains* occurrence $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n, \\ldots, \\textrm{SO}\_m \\rangle$ for every $m > n$.
+    pub fn contains(&self, other: &Occurrence) -> bool {
+        (other.path.len() > self.path.len()) &&
+        self.path.is_prefix_of(&other.path)
+    }
+
+    /// Sisterhood.
+    ///
+    /// From Definition 20, C&S 2016, p. 52.
+    ///
+    /// Something like:
+    ///
+    /// >Occurrences $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n, A \\rangle$ and $\\langle \\textrm{SO}\_1, \\ldots, \\textrm{SO}\_n, B \\rangle$ (where $A \\neq B$) are *sisters*.
+    pub fn sisters_with(&self, other: &Occurrence) -> bool {
+        let n = self.path.len();
+
+        (n >= 2) &&
+        (other.path.len() == n) &&
+        (self.path[..n - 1] == other.path[..n - 1]) &&
+        (self.path[n - 1] != other.path[n - 1])
+    }
+
+    /// C-command.
+    ///
+    /// From Definition 22, C&S 2016, p. 53.
+    ///
+    /// Something like:
+    ///
+    /// >Occurrence $O\_A$ *c-commands* occurrence $O\_B$ iff there is an occurrence $O\_C$, such that:
+    /// >
+    /// >1.  $O\_C$ is a sister of $O\_A$, and
+    /// >2.  either $O\_B = O\_C$ or $O\_C$ contains $O\_B$.
+    pub fn c_commands(&self, other: &Occurrence) -> bool {
+        let n = self.path.len();
+
+        //  The first n elements of the position of `other` make up the position of O_C.
+        (other.path.len() >= n) &&
+        Occurrence::new(Path::new(other.path[..n].to_vec())).sisters_with(self)
+    }
+
+    /// Asymmetric c-command.
+    ///
+    /// From Definition 22, C&S 2016, p. 53.
+    ///
+    /// $O\_A$ *asymmetrically c-commands* $O\_B$ iff $O\_A$ c-commands $O\_B$ and $O\_A$ and $O\_B$ are not sisters.
+    pub fn asymmetrically_c_commands(&self, other: &Occurrence) -> bool {
+        (!self.sisters_with(other)) &&
+        self.c_commands(other)
+    }
+}
+
+
+
+impl<'a> fmt::Display for Occurrence<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.so())?;
+        write!(f, " at depth {} in {}", self.path.len() - 1, SOPrefixFormatter::new(self.root(), 0))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    mod occ {
+        use crate::prelude::*;
+
+
+
+        /// `[ John [ was [ helped John ] ] ]`
+        fn get_so() -> SyntacticObject {
+            so!(
+                so!(lit!(li!("John"))),
+                so!(
+                    so!(lit!(li!("was"))),
+                    so!(
+                        so!(lit!(li!("helped"))),
+                        so!(lit!(li!("John"))),
+                    ),
+                ),
+            )
+        }
+
+
+
+        #[test]
+        fn check() {
+            let so = get_so();
+            let john = so!(lit!(li!("John")));
+            let was = so!(lit!(li!("was")));
+
+            assert!(Occurrence::new(Path::new(vec![ &so ])).check());
+            assert!(Occurrence::new(Path::new(vec![ &so, &john ])).check());
+            assert!(!Occurrence::new(Path::new(vec![])).check());
+            assert!(!Occurrence::new(Path::new(vec![ &so, &was ])).check());
+        }
+
+
+
+        #[test]
+        fn copies() {
+            let so = get_so();
+            let was_helped_john = so.contained_sos(false, false)
+                .find(|so| so.immediately_contains(&so!(lit!(li!("was")))))
+                .unwrap();
+            let helped_john = so.contained_sos(false, false)
+                .find(|so| so.immediately_contains(&so!(lit!(li!("helped")))))
+                .unwrap();
+            let john = so!(lit!(li!("John")));
+            let helped = so!(lit!(li!("helped")));
+
+            let upper = Occurrence::new(Path::new(vec![ &so, &john ]));
+            let lower = Occurrence::new(Path::new(vec![ &so, was_helped_john, helped_john, &john ]));
+            let helped = Occurrence::new(Path::new(vec![ &so, was_helped_john, helped_john, &helped ]));
+
+            assert!(upper.check());
+            assert!(lower.check());
+
+            //  Same syntactic object, different occurrences.
+            assert_eq!(upper.so(), lower.so());
+            assert_ne!(upper, lower);
+
+            //  The upper copy asymmetrically c-commands the lower copy, but not vice versa.
+            assert!(upper.c_commands(&lower));
+            assert!(upper.asymmetrically_c_commands(&lower));
+            assert!(!lower.c_commands(&upper));
+
+            //  The lower copy and "helped" are sisters.
+            assert!(lower.sisters_with(&helped));
+            assert!(lower.c_commands(&helped));
+            assert!(!lower.asymmetrically_c_commands(&helped));
+            assert!(!upper.sisters_with(&helped));
+
+            //  Containment.
+            let vp = Occurrence::new(Path::new(vec![ &so, was_helped_john, helped_john ]));
+            assert!(vp.immediately_contains(&lower));
+            assert!(vp.contains(&lower));
+            assert!(!vp.contains(&upper));
+            assert!(!vp.contains(&vp));
+            assert!(Occurrence::new(Path::new(vec![ &so ])).contains(&lower));
+            assert!(!Occurrence::new(Path::new(vec![ &so ])).immediately_contains(&lower));
+        }
+
+
+
+        #[test]
+        fn paths_to() {
+            let so = get_so();
+            let john = so!(lit!(li!("John")));
+
+            //  Two copies of "John".
+            let paths = so.paths_to(&john);
+            assert_eq!(paths.len(), 2);
+            assert!(paths.iter().all(|path| path.is_path() && path.root() == &so && path.end() == &john));
+            assert_eq!(paths.iter().map(|path| path.len()).collect::<Set<_>>(), set!(2, 4));
+
+            let occs = so.occurrences_of(&john);
+            assert!(occs[0].asymmetrically_c_commands(&occs[1]) ^ occs[1].asymmetrically_c_commands(&occs[0]));
+
+            //  The root itself, and something not contained.
+            assert_eq!(so.paths_to(&so), vec![ Path::new(vec![ &so ]) ]);
+            assert!(so.paths_to(&so!(lit!(li!("Mary")))).is_empty());
+            assert!(paths[0].is_prefix_of(&paths[0].extended_by(&john)));
+        }
+    }
+}
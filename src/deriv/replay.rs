@@ -0,0 +1,110 @@
+use crate::prelude::*;
+
+use crate::deriv::LexicalArray;
+
+use std::fmt;
+
+
+
+/// The reason why an operation script cannot be replayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayError {
+    /// The position of the failing operation in the script, counting from 1.
+    pub step: usize,
+    /// Why the operation is not applicable.
+    pub reason: OperationError,
+}
+
+
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Operation {} is not applicable.\n{}", self.step, self.reason)
+    }
+}
+
+
+
+/// Replay the operations `ops` with the UG `ug`, starting with the lexical array `la` and an empty workspace.
+///
+/// Return the resulting derivation, i.e. the first stage followed by one stage for each operation. Each operation must be applicable under the conditions of Derive-by-Select, Derive-by-Merge or Derive-by-Transfer (see [`Stage::apply`]). Otherwise, return the position of the first operation that is not applicable, with the reason.
+pub fn replay<T: Triggers>(ug: &UniversalGrammar<T>, la: LexicalArray, ops: &[Operation]) -> Result<Vec<Stage>, ReplayError> {
+    let mut stages = vec![ Stage { la, w: w!() } ];
+
+    for (k, op) in ops.iter().enumerate() {
+        my_debug!("Replay operation {}: {}", k + 1, op);
+
+        let next = stages.last().unwrap()
+            .apply(ug, op)
+            .map_err(|reason| ReplayError { step: k + 1, reason })?;
+
+        stages.push(next);
+    }
+
+    Ok(stages)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    mod replay {
+        use crate::prelude::*;
+        use crate::deriv::{ check_derivation, replay::{ replay } };
+        use crate::tests::fixtures::{ init, mary_dances };
+
+
+
+        #[test]
+        fn replay1() {
+            init();
+
+            let (il, stages) = mary_dances();
+            let ops: Vec<Operation> = check_derivation(&il, &stages).unwrap().steps
+                .into_iter()
+                .map(|step| step.op)
+                .collect();
+
+            assert_eq!(
+                replay(&il.ug, stages[0].la.clone(), &ops),
+                Ok(stages.clone())
+            );
+        }
+
+
+
+        #[test]
+        fn replay_errors() {
+            init();
+
+            let (il, stages) = mary_dances();
+            let dances = lit!(li!("dances"; "V"; "dances"), 2);
+            let v = lit!(li!("v*"; "v*", "=V", "=D";), 3);
+
+            //  Select the same token twice.
+            let err = replay(&il.ug, stages[0].la.clone(), &[
+                Operation::Select(dances.clone()),
+                Operation::Select(dances.clone()),
+            ]).unwrap_err();
+            assert_eq!(err.step, 2);
+            assert_eq!(err.reason, OperationError::NotInLexicalArray(dances.clone()));
+
+            //  Merge with a B that has trigger features.
+            let err = replay(&il.ug, stages[0].la.clone(), &[
+                Operation::Select(dances.clone()),
+                Operation::Select(v.clone()),
+                Operation::Merge(so!(dances.clone()), so!(v.clone())),
+            ]).unwrap_err();
+            assert_eq!(err.step, 3);
+            assert_eq!(err.reason, OperationError::Undefined(CandsError::NoTriggers(so!(dances.clone()))));
+
+            //  Merge with an A that is not in the workspace.
+            let err = replay(&il.ug, stages[0].la.clone(), &[
+                Operation::Select(dances.clone()),
+                Operation::Merge(so!(v.clone()), so!(dances.clone())),
+            ]).unwrap_err();
+            assert_eq!(err.step, 2);
+            assert_eq!(err.reason, OperationError::NotARoot(so!(v.clone())));
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use crate::prelude::*;
+
+use crate::deriv::{ LexicalArray, DerivationError, check_first_stage, interned::{ InternedSO } };
+
+use std::collections::{ HashMap, HashSet, VecDeque };
+
+
+
+/// Generate every derivation that starts with the lexical array `la` and has at most `depth` steps, with the phase heads of `ug`.
+///
+/// The first stage of every derivation is $\\langle \\textrm{LA}, \\{ \\} \\rangle$. Each following stage is derived from the previous one by Select, Merge, Transfer or Cyclic-Transfer, so every derivation returned here is accepted by [`is_derivation`](crate::deriv::is_derivation) (provided that the lexical item tokens in `la` are in the lexicon). This includes the derivation consisting of the first stage only.
+pub fn generate<T: Triggers>(ug: &UniversalGrammar<T>, la: LexicalArray, depth: usize) -> Vec<Vec<Stage>> {
+    let mut derivs = vec![];
+    let mut stack = vec![ vec![ Stage { la, w: w!() } ] ];
+
+    while let Some(deriv) = stack.pop() {
+        if deriv.len() <= depth {
+            let last = deriv.last().unwrap();
+
+            for (_, stage) in last.successors(ug) {
+                let mut next = deriv.clone();
+                next.push(stage);
+                stack.push(next);
+            }
+        }
+
+        derivs.push(deriv);
+    }
+
+    derivs
+}
+
+
+
+/// Search for a shortest sequence of stages from `from` to `to` that has at most `bound` steps.
+///
+/// Return the sequence, including `from` and `to`, if there is one. If `from` equals `to`, this is just `[from]`, without any step.
+///
+/// Each stage is visited at most once, and no stage from which `to` is clearly unreachable is visited. Visiting is much faster with interned syntactic objects (see [`InternedSO`]), which are hashed in constant time.
+fn find_path<T: Triggers, S: SO>(ug: &UniversalGrammar<T>, from: &Stage<S>, to: &Stage<S>, bound: usize) -> Option<Vec<Stage<S>>> {
+    //  The syntactic objects contained in `to`, including those inside transferred syntactic objects.
+    let in_to: HashSet<&S> = to.w.contained_sos(false).collect();
+
+    //  For each visited stage, remember the stage it was reached from and its distance from `from`.
+    let mut visited: HashMap<Stage<S>, (Option<Stage<S>>, usize)> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    visited.insert(from.clone(), (None, 0));
+    queue.push_back(from.clone());
+
+    while let Some(stage) = queue.pop_front() {
+        if &stage == to {
+            let mut path = vec![ stage ];
+            while let Some((Some(prev), _)) = visited.get(path.last().unwrap()) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let dist = visited[&stage].1;
+        if dist >= bound {
+            continue;
+        }
+
+        for (_, next) in stage.successors(ug) {
+            //  Lexical item tokens never return to the lexical array, so `to` is unreachable from `next` unless `next` still has all of them.
+            if !to.la.is_subset(&next.la) || visited.contains_key(&next) {
+                continue;
+            }
+
+            //  A transferred syntactic object never changes, and it stays contained in some root of every later stage. So `to` is unreachable from `next` if `next` has a transferred syntactic object that `to` does not.
+            if next.w.contained_sos(false).any(|so| so.is_transfer() && !in_to.contains(so)) {
+                continue;
+            }
+
+            visited.insert(next.clone(), (Some(stage.clone()), dist + 1));
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+
+
+/// Complete the sparse derivation `checkpoints` from the I-language `il`.
+///
+/// Consecutive stages in `checkpoints` may be separated by several steps, but at most `bound` steps. Return the complete derivation, which contains every stage in `checkpoints` in the same order, with the missing stages in between. Where there is more than one way to fill a gap, a shortest one is used.
+///
+/// Otherwise, return the reason why `checkpoints` cannot be completed. In particular, [`DerivationError::Unreachable`] means that a checkpoint cannot be reached from the previous one in at most `bound` steps. Two equal consecutive checkpoints are not merged into one, but rejected with [`DerivationError::NoMatchingRule`], as in [`check_derivation`](crate::deriv::check_derivation), since no operation leaves a stage unchanged. Here, stages are numbered by their position in `checkpoints`, counting from 1.
+pub fn fill_gaps<T: Triggers>(il: &ILanguage<T>, checkpoints: &[Stage], bound: usize) -> Result<Vec<Stage>, DerivationError> {
+    check_first_stage(il, checkpoints)?;
+
+    let mut deriv = vec![ checkpoints[0].clone() ];
+
+    for (k, pair) in checkpoints.windows(2).enumerate() {
+        my_debug!("Search for a path from checkpoint {} to checkpoint {}...", k + 1, k + 2);
+
+        //  A path of length 0 is not a step.
+        if pair[0] == pair[1] {
+            return Err(DerivationError::NoMatchingRule { stage: k + 1, reason: None });
+        }
+
+        let from = Stage::<InternedSO>::from(&pair[0]);
+        let to = Stage::<InternedSO>::from(&pair[1]);
+        let path = find_path(&il.ug, &from, &to, bound)
+            .ok_or(DerivationError::Unreachable { stage: k + 1, bound })?;
+
+        deriv.extend(path.iter().skip(1).map(Stage::from));
+    }
+
+    Ok(deriv)
+}
+
+
+
+/// Derivability from lexicon.
+///
+/// From Definition 15, C&S 2016, p. 48. Here, derivability is defined with respect to an I-language rather than a lexicon (see [`check_derivation`](crate::deriv::check_derivation)).
+///
+/// >A syntactic object $\\textrm{SO}$ is *derivable* from lexicon $L$ iff there is a derivation $\\langle \\textrm{LA}\_1, W\_1 \\rangle, \\ldots, \\langle \\textrm{LA}\_n, W\_n \\rangle$ from $L$ such that $\\textrm{LA}\_n = \\{ \\}$ and $W\_n = \\{ \\textrm{SO} \\}$.
+///
+/// Return a shortest such derivation if there is one. Its first lexical array consists of exactly the lexical item tokens contained in `so`, since any other token would remain in $\\textrm{LA}\_n$ or $W\_n$.
+///
+/// The search is breadth-first, so it may take time exponential in the size of `so`, although each stage is visited at most once.
+pub fn is_derivable<T: Triggers>(il: &ILanguage<T>, so: &SyntacticObject) -> Option<Vec<Stage>> {
+    let sos: Vec<&SyntacticObject> = so.contained_sos(true, false).collect();
+
+    let la = LexicalArray::new(
+        sos.iter()
+            .filter_map(|so| match so {
+                SyntacticObject::LexicalItemToken(lit) => Some(lit.clone()),
+                _ => None,
+            })
+            .collect()
+    );
+
+    let first = Stage { la, w: w!() };
+    let last = Stage { la: LexicalArray::new(set!()), w: w!(so.clone()) };
+
+    if let Err(e) = check_first_stage(il, std::slice::from_ref(&first)) {
+        my_info!("{}", e);
+        return None;
+    }
+
+    //  Every node of `so` is built by one step, i.e. Select for lexical item tokens, Merge for sets, and Transfer or Cyclic-Transfer for transferred syntactic objects. Copies created by internal Merge are counted more than once, so this is an upper bound.
+    let path = find_path(&il.ug, &Stage::<InternedSO>::from(&first), &Stage::<InternedSO>::from(&last), sos.len())?;
+    Some(path.iter().map(Stage::from).collect())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    mod search {
+        use crate::prelude::*;
+        use crate::deriv::{ LexicalArray, DerivationError, check_derivation, search::{ generate, fill_gaps, is_derivable } };
+        use crate::tests::fixtures::{ init, mary_dances, mary_helped_john };
+
+
+
+        #[test]
+        fn generate1() {
+            init();
+
+            let (il, stages) = mary_dances();
+
+            //  Only use "Mary", "dances" and "v*".
+            let la = LexicalArray::new(set!(
+                lit!(li!("Mary"; "D"; "Mary"), 1),
+                lit!(li!("dances"; "V"; "dances"), 2),
+                lit!(li!("v*"; "v*", "=V", "=D";), 3)
+            ));
+            let derivs = generate(&il.ug, la.clone(), 5);
+
+            //  The trivial derivation is included.
+            assert!(derivs.contains(&vec![ Stage { la, w: Workspace::new(set!()) } ]));
+
+            //  So is the hand-written derivation of "Mary dances", minus T and C.
+            let mut expected: Vec<Stage> = stages[..6].to_vec();
+            for stage in expected.iter_mut() {
+                stage.la.remove(&lit!(li!("PRES"; "T", "=v*";), 4));
+                stage.la.remove(&lit!(li!("C"; "C", "=T";), 5));
+            }
+            assert!(derivs.contains(&expected));
+
+            //  Every generated sequence is a derivation, and no sequence is generated twice.
+            for deriv in derivs.iter() {
+                assert!(deriv.len() <= 6);
+                assert!(is_derivation(&il, deriv));
+            }
+            assert_eq!(derivs.iter().collect::<Set<_>>().len(), derivs.len());
+        }
+
+
+
+        #[test]
+        fn successors1() {
+            init();
+
+            let (il, stages) = mary_dances();
+
+            //  From the first stage, we can only select.
+            let succs: Vec<(Operation, Stage)> = stages[0].successors(&il.ug).collect();
+            assert_eq!(succs.len(), 5);
+            assert!(succs.iter().all(|(op, _)| matches!(op, Operation::Select(_))));
+            assert!(succs.contains(&(
+                Operation::Select(lit!(li!("dances"; "V"; "dances"), 2)),
+                stages[1].clone()
+            )));
+
+            //  With "dances" and "v*" in the workspace, we can select, merge, or
+            //  transfer "v*" (which is trivially a strong phase).
+            let succs: Vec<(Operation, Stage)> = stages[2].successors(&il.ug).collect();
+            assert_eq!(succs.len(), 5);
+            assert!(succs.iter().any(|(op, _)| {
+                op == &Operation::Transfer(so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)))
+            }));
+            assert!(succs.contains(&(
+                Operation::Merge(
+                    so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
+                    so!(lit!(li!("dances"; "V"; "dances"), 2))
+                ),
+                stages[3].clone()
+            )));
+
+            //  Every successor of every stage is derivable from that stage.
+            for k in 0..stages.len() {
+                for (_, next) in stages[k].successors(&il.ug) {
+                    let mut deriv = stages[..=k].to_vec();
+                    deriv.push(next);
+                    assert!(is_derivation(&il, &deriv));
+                }
+            }
+        }
+
+
+
+        #[test]
+        fn fill_gaps1() {
+            init();
+
+            let (il, stages) = mary_dances();
+
+            //  Keep the first stage, the vP, the CP and the transferred CP.
+            let checkpoints = vec![
+                stages[0].clone(),
+                stages[5].clone(),
+                stages[10].clone(),
+                stages[11].clone(),
+            ];
+            let deriv = fill_gaps(&il, &checkpoints, 5).unwrap();
+
+            assert_eq!(deriv.len(), stages.len());
+            assert!(is_derivation(&il, &deriv));
+            for checkpoint in checkpoints.iter() {
+                assert!(deriv.contains(checkpoint));
+            }
+            assert_eq!(deriv.last(), stages.last());
+
+            //  A complete derivation is its own completion.
+            assert_eq!(fill_gaps(&il, &stages, 1), Ok(stages.clone()));
+        }
+
+
+
+        #[test]
+        fn fill_gaps_errors() {
+            init();
+
+            let (il, stages) = mary_dances();
+
+            //  The vP needs 5 steps.
+            assert_eq!(
+                fill_gaps(&il, &[ stages[0].clone(), stages[5].clone() ], 4),
+                Err(DerivationError::Unreachable { stage: 1, bound: 4 })
+            );
+
+            //  Going backwards is impossible.
+            assert_eq!(
+                fill_gaps(&il, &[ stages[0].clone(), stages[5].clone(), stages[4].clone() ], 5),
+                Err(DerivationError::Unreachable { stage: 2, bound: 5 })
+            );
+
+            assert_eq!(
+                fill_gaps(&il, &stages[1..], 5),
+                Err(DerivationError::NonEmptyFirstWorkspace)
+            );
+
+            //  A repeated checkpoint is rejected, as by the checker.
+            assert_eq!(
+                fill_gaps(&il, &[ stages[0].clone(), stages[5].clone(), stages[5].clone() ], 5),
+                Err(DerivationError::NoMatchingRule { stage: 2, reason: None })
+            );
+            let mut repeated = stages[..=5].to_vec();
+            repeated.push(stages[5].clone());
+            assert_eq!(
+                check_derivation(&il, &repeated),
+                Err(DerivationError::NoMatchingRule { stage: 6, reason: None })
+            );
+        }
+
+
+
+        #[test]
+        fn is_derivable1() {
+            init();
+
+            let (il, stages) = mary_dances();
+
+            //  The transferred CP at the end of the derivation.
+            let cp = stages.last().unwrap().w.iter().next().unwrap().clone();
+            let deriv = is_derivable(&il, &cp).unwrap();
+            assert!(is_derivation(&il, &deriv));
+            assert_eq!(deriv.len(), stages.len());
+            assert_eq!(deriv.last(), stages.last());
+
+            //  "Mary" and "dances" cannot be merged directly.
+            let mary = lit!(li!("Mary"; "D"; "Mary"), 1);
+            let dances = lit!(li!("dances"; "V"; "dances"), 2);
+            assert_eq!(is_derivable(&il, &so!(so!(mary.clone()), so!(dances.clone()),)), None);
+
+            //  A lexical item token is derivable on its own, unless its lexical item is not in the lexicon.
+            assert_eq!(is_derivable(&il, &so!(mary.clone())).map(|deriv| deriv.len()), Some(2));
+            assert_eq!(is_derivable(&il, &so!(lit!(li!("Sue"; "D"; "Sue"), 6))), None);
+        }
+
+
+
+        #[test]
+        fn pic_policy() {
+            init();
+
+            let (mut il, stages) = mary_helped_john();
+
+            //  "John" moves out of the transferred VP at the last step, which only the checker without PIC accepts.
+            let cp = stages.last().unwrap().w.iter().next().unwrap().clone();
+            for pic_policy in [ PicPolicy::None, PicPolicy::Pic1, PicPolicy::Pic2 ] {
+                il.ug.pic_policy = pic_policy;
+
+                for k in 0..(stages.len() - 1) {
+                    let checked = is_derivation(&il, &stages[..=(k + 1)]);
+                    let succeeded = stages[k].successors(&il.ug).any(|(_, next)| next == stages[k + 1]);
+                    assert_eq!(checked, succeeded, "{:?}, stage {}", pic_policy, k + 1);
+                }
+
+                let derived = is_derivable(&il, &cp);
+                assert_eq!(derived.is_some(), is_derivation(&il, &stages), "{:?}", pic_policy);
+                if let Some(deriv) = derived {
+                    assert!(is_derivation(&il, &deriv));
+                }
+            }
+        }
+    }
+}
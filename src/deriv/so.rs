@@ -1,6 +1,7 @@
 use crate::prelude::*;
 
 use std::fmt;
+use std::hash::Hash;
 
 
 
@@ -32,7 +33,7 @@ pub enum SyntacticObject {
 
 impl fmt::Display for SyntacticObject {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.fmt_with_prefix("", "", false, true, f)
+        fmt_with_prefix(self, "", "", false, true, f)
     }
 }
 
@@ -97,18 +98,48 @@ pub(crate) use so;
 
 
 
-impl SyntacticObject {
-    pub fn is_lexical_item_token(&self) -> bool {
-        match self {
-            &SyntacticObject::LexicalItemToken(_) => true,
-            _ => false,
-        }
+/// One level of a syntactic object, i.e. what it is made of, whatever its representation.
+///
+/// See [`SO::view`].
+#[derive(Debug)]
+pub enum SOView<'a, S> {
+    LexicalItemToken(&'a LexicalItemToken),
+    Set(&'a Set<S>),
+    Transfer {
+        so: &'a S,
+        pf: &'a [Feature],
+        lf: &'a Set<Feature>,
+    },
+}
+
+
+
+/// A representation of syntactic objects.
+///
+/// An implementation only has to say how to look at one level of a syntactic object (see [`SO::view`]) and how to build one. The relations between syntactic objects come for free, but an implementation may override them, e.g. to answer them faster. [`SyntacticObject`] is the reference implementation, which follows Definition 37 of C&S 2016 as closely as possible.
+pub trait SO: Clone + Eq + Ord + Hash + fmt::Debug + fmt::Display {
+    /// Return what `self` is made of.
+    fn view(&self) -> SOView<'_, Self>;
+
+    /// Return the syntactic object that is the lexical item token `lit`.
+    fn from_lit(lit: LexicalItemToken) -> Self;
+
+    /// Return the syntactic object that is the set `set` of syntactic objects.
+    fn from_set(set: Set<Self>) -> Self;
+
+    /// Return the result of transferring `so`, with the PF `pf` and the LF `lf`.
+    fn from_transfer(so: Self, pf: Vec<Feature>, lf: Set<Feature>) -> Self;
+
+    fn is_lexical_item_token(&self) -> bool {
+        matches!(self.view(), SOView::LexicalItemToken(_))
     }
-    pub fn is_set(&self) -> bool {
-        match self {
-            &SyntacticObject::Set(_) => true,
-            _ => false,
-        }
+
+    fn is_set(&self) -> bool {
+        matches!(self.view(), SOView::Set(_))
+    }
+
+    fn is_transfer(&self) -> bool {
+        matches!(self.view(), SOView::Transfer { .. })
     }
 
     /// Immediate containment.
@@ -116,14 +147,14 @@ impl SyntacticObject {
     /// From Definition 8, C&S 2016, p. 46.
     /// 
     /// >Let $A$ and $B$ be syntactic objects, then $B$ *immediately contains* $A$ iff $A \\in B$.
-    pub fn immediately_contains(&self, a: &SyntacticObject) -> bool {
-        match self {
+    fn immediately_contains(&self, a: &Self) -> bool {
+        match self.view() {
             //  A lexical item token does not immediately contain any syntactic object because it is not a set.
-            &SyntacticObject::LexicalItemToken(_) => false,
-            &SyntacticObject::Set(ref b) => b.contains(a),
+            SOView::LexicalItemToken(_) => false,
+            SOView::Set(b) => b.contains(a),
             //  Transfer(PF, LF) is not a syntactic object, so it does not immediately contain anything
-            &SyntacticObject::Transfer { ref so, .. } =>
-                **so == *a,
+            SOView::Transfer { so, .. } =>
+                so == a,
         }
     }
 
@@ -135,16 +166,16 @@ impl SyntacticObject {
     /// >
     /// >1.  $B$ immediately contains $A$, or
     /// >2.  for some syntactic object $C$, $B$ immediately contains $C$ and $C$ contains $A$.
-    pub fn contains(&self, a: &SyntacticObject) -> bool {
-        match self {
+    fn contains(&self, a: &Self) -> bool {
+        match self.view() {
             //  A lexical item token does not contain any syntactic object because it is not a set.
-            &SyntacticObject::LexicalItemToken(_) => false,
-            &SyntacticObject::Set(ref b) => {
+            SOView::LexicalItemToken(_) => false,
+            SOView::Set(b) => {
                 b.contains(a) ||
                 b.iter().any(|so| so.contains(a))
             },
-            &SyntacticObject::Transfer { ref so, .. } =>
-                (**so == *a) || so.contains(a),
+            SOView::Transfer { so, .. } =>
+                (so == a) || so.contains(a),
         }
     }
 
@@ -153,7 +184,7 @@ impl SyntacticObject {
     /// From Definition 11, C&S 2016, p. 47.
     /// 
     /// >For any syntactic object $X$ and any stage $S = \\langle \textrm{LA}, W \\rangle$ with workspace $W$, if $X \\in W$, $X$ is a *root* in $W$.
-    pub fn is_root(&self, w: &Workspace) -> bool {
+    fn is_root(&self, w: &Workspace<Self>) -> bool {
         w.immediately_contains(self)
     }
 
@@ -162,7 +193,7 @@ impl SyntacticObject {
     /// From Definition 19, C&S 2016, p. 52.
     /// 
     /// >Let $A$, $B$, $C$ be syntactic objects (where $A \\neq B$), then $A$ and $B$ are *sisters* in $C$ iff $A, B \\in C$.
-    pub fn sisters_with(&self, other: &SyntacticObject, under: &SyntacticObject) -> bool {
+    fn sisters_with(&self, other: &Self, under: &Self) -> bool {
         (self != other) &&
         under.immediately_contains(self) &&
         under.immediately_contains(other)
@@ -176,14 +207,10 @@ impl SyntacticObject {
     /// >
     /// >1.  $C$ is a sister of $A$ in $D$, and
     /// >2.  either $B = C$ or $C$ contains $B$.
-    pub fn c_commands(&self, other: &SyntacticObject, under: &SyntacticObject) -> bool {
-        //  `under` is D in the definition.
-        under.contained_sos(false, false)
-            .any(|so| {
-                //  `so` is C in the definition.
-                so.sisters_with(self, under) &&
-                (other == so || so.contains(other))
-            })
+    ///
+    /// Only the syntactic objects accessible in `lw` under the PIC of its UG can be c-commanded (see [`UniversalGrammar::pic_policy`]).
+    fn c_commands<T: Triggers>(&self, lw: &LabeledWorkspace<T, Self>, other: &Self, under: &Self) -> bool {
+        lw.c_commands(self, other, under)
     }
 
     /// Asymmetric c-command.
@@ -191,9 +218,9 @@ impl SyntacticObject {
     /// From Definition 21, C&S 2016, p. 53.
     /// 
     /// $A$ *asymmetrically c-commands* $B$ iff $A$ c-commands $B$ and $A$ and $B$ are not sisters.
-    pub fn asymmetrically_c_commands(&self, other: &SyntacticObject, under: &SyntacticObject) -> bool {
+    fn asymmetrically_c_commands<T: Triggers>(&self, lw: &LabeledWorkspace<T, Self>, other: &Self, under: &Self) -> bool {
         (!self.sisters_with(other, under)) &&
-        self.c_commands(other, under)
+        self.c_commands(lw, other, under)
     }
 
     /// Binary branching.
@@ -201,22 +228,22 @@ impl SyntacticObject {
     /// From Definition 24, C&S 2016, p. 57.
     /// 
     /// >Syntactic object $A$ is *binary branching* iff both $A$ and everything contained in $A$ is either a lexical item token or a syntactic object immediately containing exactly two syntactic objects.
-    pub fn is_binary_branching(&self) -> bool {
-        match self {
-            &SyntacticObject::LexicalItemToken(_) => true,
-            &SyntacticObject::Set(ref set) => {
+    fn is_binary_branching(&self) -> bool {
+        match self.view() {
+            SOView::LexicalItemToken(_) => true,
+            SOView::Set(set) => {
                 set.iter()
                     .all(|so| so.is_binary_branching()) &&
                 (set.len() == 2)
             },
-            &SyntacticObject::Transfer { ref so, .. } =>
+            SOView::Transfer { so, .. } =>
                 so.is_binary_branching(),
         }
     }
 
     /// Return an iterator over all the syntactic objects contained in `self`.
-    pub fn contained_sos(&self, start_with_self: bool, pic_compliant: bool) -> ContainedSyntacticObjects {
-        let stack: Vec<&SyntacticObject> = vec![ self ];
+    fn contained_sos(&self, start_with_self: bool, pic_compliant: bool) -> ContainedSyntacticObjects<'_, Self> {
+        let stack: Vec<&Self> = vec![ self ];
         let mut it = ContainedSyntacticObjects::new(stack, pic_compliant);
         if !start_with_self {
             it.next();
@@ -224,165 +251,215 @@ impl SyntacticObject {
         it
     }
 
-    /// Recursive function used by the `fmt::Display` implementation to pretty-print the current syntactic object.
-    fn fmt_with_prefix(&self,
-        prefix1: &str,
-        prefix2: &str,
-        newline: bool,
-        first: bool,
-        f: &mut fmt::Formatter<'_>
-    ) -> fmt::Result {
-        match self {
-            &SyntacticObject::LexicalItemToken(ref lit) =>
-                write!(f,
-                    "{}{}{}{}",
-                    prefix1,
-                    if first { "" } else { "─ " },
-                    lit,
-                    if newline { "\n" } else { "" }
-                ),
+    fn is_maximal_projection_of<T: Triggers>(&self, lw: &LabeledWorkspace<T, Self>, lit: &LexicalItemToken) -> bool {
+        lw.is_maximal_projection_of(self, lit)
+    }
 
-            &SyntacticObject::Set(ref set) => {
-                if set.is_empty() {
-                    return write!(f,
-                        "{}{}Ø{}",
-                        prefix1,
-                        if first { "" } else { "─ " },
-                        if newline { "\n" } else { "" }
-                    );
-                }
+    fn is_minimal_projection(&self) -> bool {
+        self.is_lexical_item_token()
+    }
 
-                if set.len() == 1 {
-                    let newprefix1 = format!("{}{}═", prefix1, if first { " " } else { "─" });
-                    let newprefix2 = format!("{}{} ", prefix2, if first { " " } else { "─" });
-                    return set.iter().next().unwrap()
-                        .fmt_with_prefix(&newprefix1, &newprefix2, newline, false, f);
-                }
+    fn is_intermediate_projection_of<T: Triggers>(&self, lw: &LabeledWorkspace<T, Self>, lit: &LexicalItemToken) -> bool {
+        (!self.is_maximal_projection_of(lw, lit)) &&
+        (!self.is_minimal_projection())
+    }
 
-                let mut it = set.iter();
+    fn is_complement_of<T: Triggers>(&self, lw: &LabeledWorkspace<T, Self>, head: &Self, under: &Self) -> bool {
+        lw.is_complement_of(self, head, under)
+    }
 
-                let newprefix1 = format!("{}{}", prefix1, if first { " ╔" } else { "─╦" });
-                //let newprefix2 = format!("{}{}║", prefix1, if first { " " } else { "─" });
-                let newprefix2 = format!("{} ║", prefix2);
-                it.next().unwrap()
-                    .fmt_with_prefix(&newprefix1, &newprefix2, true, false, f)?;
+    fn is_specifier_of<T: Triggers>(&self, lw: &LabeledWorkspace<T, Self>, head: &Self, under: &Self) -> bool {
+        lw.is_specifier_of(self, head, under)
+    }
 
-                let newprefix1 = format!("{} ╠", prefix2);
-                let newprefix2 = format!("{} ║", prefix2);
-                for _ in 0..(set.len() - 2) {
-                    it.next().unwrap()
-                        .fmt_with_prefix(&newprefix1, &newprefix2, true, false, f)?;
-                }
+    fn is_final(&self, parent: &Self, under: &Self) -> bool {
+        assert!(parent.immediately_contains(self));
 
-                let newprefix1 = format!("{} ╚", prefix2);
-                let newprefix2 = format!("{}  ", prefix2);
-                let res = it.next().unwrap()
-                    .fmt_with_prefix(&newprefix1, &newprefix2, newline, false, f);
+        !under.contained_sos(true, true)
+            .any(|c| {
+                c.immediately_contains(self) && c.contains(parent)
+            })
+    }
+}
 
-                assert_eq!(it.next(), None);
-                res
-            },
 
-            &SyntacticObject::Transfer { ref so, ref pf, ref lf } => {
-                const BORDER: &'static str = "━━━━━━━━━━━━━━━━━━━━";
-                let newprefix1 = format!("{} ┃", prefix2);
-                let newprefix2 = format!("{} ┃", prefix2);
-                write!(f, "{}{}{}\n", prefix1, if first { " ┏" } else { "─┳" }, BORDER)?;
-                so.fmt_with_prefix(&newprefix1, &newprefix2, true, true, f)?;
-                write!(f, "{} ┣{}\n", prefix2, BORDER)?;
-                write!(f, "{} ┃ PF: [ {} ]\n", prefix2, pf.iter().map(|f| f.0.to_owned()).reduce(|a, b| format!("{} {}", a, b)).unwrap_or_else(|| format!("")))?;
-                write!(f, "{} ┃ LF: {{ {} }}\n", prefix2, lf.iter().map(|f| f.0.to_owned()).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_else(|| format!("")))?;
-                write!(f, "{} ┗{}{}", prefix2, BORDER, if newline { "\n" } else { "" })
-            },
+
+impl SO for SyntacticObject {
+    fn view(&self) -> SOView<'_, Self> {
+        match self {
+            SyntacticObject::LexicalItemToken(lit) => SOView::LexicalItemToken(lit),
+            SyntacticObject::Set(set) => SOView::Set(set),
+            SyntacticObject::Transfer { so, pf, lf } => SOView::Transfer { so, pf, lf },
         }
     }
 
-    pub fn is_maximal_projection_of<T: Triggers>(&self, lit: &LexicalItemToken, w: &Workspace) -> bool {
-        match T::label_of(self, w) {
-            Ok(label) => {
-                (label == lit) &&
-                (!w.contained_sos(false)
-                    .any(|d| {
-                        d.immediately_contains(self) &&
-                        (T::label_of(d, w) == T::label_of(self, w))
-                    }))
-            },
-            Err(_) => false,
-        }
+    fn from_lit(lit: LexicalItemToken) -> Self {
+        so!(lit)
     }
 
-    pub fn is_minimal_projection(&self) -> bool {
-        match self {
-            &SyntacticObject::LexicalItemToken(_) => true,
-            _ => false,
-        }
+    fn from_set(set: Set<Self>) -> Self {
+        SyntacticObject::Set(set)
     }
 
-    pub fn is_intermediate_projection_of<T: Triggers>(&self, lit: &LexicalItemToken, w: &Workspace) -> bool {
-        (!self.is_maximal_projection_of::<T>(lit, w)) &&
-        (!self.is_minimal_projection())
+    fn from_transfer(so: Self, pf: Vec<Feature>, lf: Set<Feature>) -> Self {
+        so!(so => pf; lf)
     }
+}
 
-    pub fn is_complement_of<T: Triggers>(&self, head: &SyntacticObject, under: &SyntacticObject, w: &Workspace) -> bool {
-        match triggered_merge::<T>(head.clone(), self.clone(), w) {
-            Ok(merged) => {
-                (under == &merged) && head.is_lexical_item_token()
-            },
-            _ => false,
+
+
+impl SyntacticObject {
+    /// Positions.
+    ///
+    /// From Definition 16, C&S 2016, p. 50.
+    ///
+    /// Return every position of `target` in `self`, i.e. every path from `self` down to `target`, in order. If `target` is `self`, the only position is $\\langle \\textrm{self} \\rangle$. If `self` does not contain `target`, there is none.
+    ///
+    /// Unlike [`SO::contains`], this tells the copies of an internally merged syntactic object apart.
+    pub fn paths_to<'a>(&'a self, target: &SyntacticObject) -> Vec<Path<'a>> {
+        let mut paths = vec![];
+        let mut stack = vec![ Path::new(vec![ self ]) ];
+
+        while let Some(path) = stack.pop() {
+            let end = path.end();
+
+            if end == target {
+                //  A syntactic object never contains itself, so there is no need to look further down.
+                paths.push(path);
+                continue;
+            }
+
+            match end {
+                SyntacticObject::LexicalItemToken(_) => (),
+                SyntacticObject::Set(children) => {
+                    for child in children {
+                        stack.push(path.extended_by(child));
+                    }
+                },
+                SyntacticObject::Transfer { so, .. } =>
+                    stack.push(path.extended_by(so)),
+            }
         }
+
+        paths.sort();
+        paths
     }
 
-    pub fn is_specifier_of<T: Triggers>(&self, head: &SyntacticObject, under: &SyntacticObject, w: &Workspace) -> bool {
-        match triggered_merge::<T>(head.clone(), self.clone(), w) {
-            Ok(merged) => {
-                (under == &merged) && head.is_set()
-            },
-            _ => false,
-        }
+    /// Occurrences.
+    ///
+    /// From Definition 17, C&S 2016, p. 51.
+    ///
+    /// Return every occurrence of `target` in `self`. See [`SyntacticObject::paths_to`].
+    pub fn occurrences_of<'a>(&'a self, target: &SyntacticObject) -> Vec<Occurrence<'a>> {
+        self.paths_to(target).into_iter()
+            .map(Occurrence::new)
+            .collect()
     }
+}
 
-    pub fn is_final(&self, parent: &SyntacticObject, under: &SyntacticObject) -> bool {
-        assert!(parent.immediately_contains(self));
 
-        !under.contained_sos(true, true)
-            .any(|c| {
-                c.immediately_contains(self) && c.contains(parent)
-            })
+
+/// Recursive function used by the `fmt::Display` implementations to pretty-print the syntactic object `so`.
+fn fmt_with_prefix<S: SO>(so: &S,
+    prefix1: &str,
+    prefix2: &str,
+    newline: bool,
+    first: bool,
+    f: &mut fmt::Formatter<'_>
+) -> fmt::Result {
+    match so.view() {
+        SOView::LexicalItemToken(lit) =>
+            write!(f,
+                "{}{}{}{}",
+                prefix1,
+                if first { "" } else { "─ " },
+                lit,
+                if newline { "\n" } else { "" }
+            ),
+
+        SOView::Set(set) => {
+            if set.is_empty() {
+                return write!(f,
+                    "{}{}Ø{}",
+                    prefix1,
+                    if first { "" } else { "─ " },
+                    if newline { "\n" } else { "" }
+                );
+            }
+
+            if set.len() == 1 {
+                let newprefix1 = format!("{}{}═", prefix1, if first { " " } else { "─" });
+                let newprefix2 = format!("{}{} ", prefix2, if first { " " } else { "─" });
+                return fmt_with_prefix(set.iter().next().unwrap(), &newprefix1, &newprefix2, newline, false, f);
+            }
+
+            let mut it = set.iter();
+
+            let newprefix1 = format!("{}{}", prefix1, if first { " ╔" } else { "─╦" });
+            //let newprefix2 = format!("{}{}║", prefix1, if first { " " } else { "─" });
+            let newprefix2 = format!("{} ║", prefix2);
+            fmt_with_prefix(it.next().unwrap(), &newprefix1, &newprefix2, true, false, f)?;
+
+            let newprefix1 = format!("{} ╠", prefix2);
+            let newprefix2 = format!("{} ║", prefix2);
+            for _ in 0..(set.len() - 2) {
+                fmt_with_prefix(it.next().unwrap(), &newprefix1, &newprefix2, true, false, f)?;
+            }
+
+            let newprefix1 = format!("{} ╚", prefix2);
+            let newprefix2 = format!("{}  ", prefix2);
+            let res = fmt_with_prefix(it.next().unwrap(), &newprefix1, &newprefix2, newline, false, f);
+
+            assert!(it.next().is_none());
+            res
+        },
+
+        SOView::Transfer { so, pf, lf } => {
+            const BORDER: &'static str = "━━━━━━━━━━━━━━━━━━━━";
+            let newprefix1 = format!("{} ┃", prefix2);
+            let newprefix2 = format!("{} ┃", prefix2);
+            write!(f, "{}{}{}\n", prefix1, if first { " ┏" } else { "─┳" }, BORDER)?;
+            fmt_with_prefix(so, &newprefix1, &newprefix2, true, true, f)?;
+            write!(f, "{} ┣{}\n", prefix2, BORDER)?;
+            write!(f, "{} ┃ PF: [ {} ]\n", prefix2, pf.iter().map(|f| f.0.to_owned()).reduce(|a, b| format!("{} {}", a, b)).unwrap_or_else(|| format!("")))?;
+            write!(f, "{} ┃ LF: {{ {} }}\n", prefix2, lf.iter().map(|f| f.0.to_owned()).reduce(|a, b| format!("{}, {}", a, b)).unwrap_or_else(|| format!("")))?;
+            write!(f, "{} ┗{}{}", prefix2, BORDER, if newline { "\n" } else { "" })
+        },
     }
 }
 
 
 
-/// An iterator over the syntactic objects contained in a `SyntacticObject`.
-pub struct ContainedSyntacticObjects<'a> {
+/// An iterator over the syntactic objects contained in a syntactic object.
+pub struct ContainedSyntacticObjects<'a, S: SO = SyntacticObject> {
     /// The stack of syntactic objects that this iterator is supposed to visit.
-    stack: Vec<&'a SyntacticObject>,
+    stack: Vec<&'a S>,
     pic_compliant: bool,
 }
 
 
 
-impl<'a> ContainedSyntacticObjects<'a> {
-    fn new(stack: Vec<&'a SyntacticObject>, pic_compliant: bool) -> Self {
+impl<'a, S: SO> ContainedSyntacticObjects<'a, S> {
+    pub(crate) fn new(stack: Vec<&'a S>, pic_compliant: bool) -> Self {
         Self { stack, pic_compliant }
     }
 }
 
 
 
-impl<'a> Iterator for ContainedSyntacticObjects<'a> {
-    type Item = &'a SyntacticObject;
+impl<'a, S: SO> Iterator for ContainedSyntacticObjects<'a, S> {
+    type Item = &'a S;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.stack.pop()
             .map(|so| {
-                match so {
-                    &SyntacticObject::Set(ref children) => {
+                match so.view() {
+                    SOView::Set(children) => {
                         for child in children {
                             self.stack.push(child);
                         }
                     },
-                    &SyntacticObject::Transfer { ref so, .. } => {
+                    SOView::Transfer { so, .. } => {
                         if !self.pic_compliant {
                             self.stack.push(so);
                         }
@@ -398,15 +475,15 @@ impl<'a> Iterator for ContainedSyntacticObjects<'a> {
 
 
 #[derive(Debug)]
-pub struct SOPrefixFormatter<'a> {
-    so: &'a SyntacticObject,
+pub struct SOPrefixFormatter<'a, S: SO = SyntacticObject> {
+    so: &'a S,
     spaces: String,
 }
 
 
 
-impl<'a> SOPrefixFormatter<'a> {
-    pub fn new(so: &'a SyntacticObject, num_spaces: usize) -> Self {
+impl<'a, S: SO> SOPrefixFormatter<'a, S> {
+    pub fn new(so: &'a S, num_spaces: usize) -> Self {
         Self {
             so,
             spaces: std::iter::repeat(" ").take(num_spaces).collect::<String>(),
@@ -416,9 +493,10 @@ impl<'a> SOPrefixFormatter<'a> {
 
 
 
-impl<'a> fmt::Display for SOPrefixFormatter<'a> {
+impl<'a, S: SO> fmt::Display for SOPrefixFormatter<'a, S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.so.fmt_with_prefix(
+        fmt_with_prefix(
+            self.so,
             "",
             &self.spaces,
             false,
@@ -813,8 +891,12 @@ mod tests {
                 so!(lit!(li!("B"))),
             );
 
-            assert!(a.c_commands(&b, &ab));
-            assert!(b.c_commands(&a, &ab));
+            let ug = UniversalGrammar::<BasicTriggers>::new(set!(), set!(), set!());
+            let w = w!(ab.clone());
+            let lw = LabeledWorkspace::with_ug(&ug, &w);
+
+            assert!(a.c_commands(&lw, &b, &ab));
+            assert!(b.c_commands(&lw, &a, &ab));
         }
 
 
@@ -847,15 +929,19 @@ mod tests {
                 so!(lit!(li!("C"))),
             );
 
-            assert!(a.c_commands(&bc, &a_bc));
-            assert!(bc.c_commands(&a, &a_bc));
-            assert!(ab.c_commands(&c, &ab_c));
-            assert!(c.c_commands(&ab, &ab_c));
+            let ug = UniversalGrammar::<BasicTriggers>::new(set!(), set!(), set!());
+            let w = w!(a_bc.clone(), ab_c.clone());
+            let lw = LabeledWorkspace::with_ug(&ug, &w);
+
+            assert!(a.c_commands(&lw, &bc, &a_bc));
+            assert!(bc.c_commands(&lw, &a, &a_bc));
+            assert!(ab.c_commands(&lw, &c, &ab_c));
+            assert!(c.c_commands(&lw, &ab, &ab_c));
 
-            assert!(a.c_commands(&c, &a_bc));
-            assert!(!c.c_commands(&a, &a_bc));
-            assert!(c.c_commands(&a, &ab_c));
-            assert!(!a.c_commands(&c, &ab_c));
+            assert!(a.c_commands(&lw, &c, &a_bc));
+            assert!(!c.c_commands(&lw, &a, &a_bc));
+            assert!(c.c_commands(&lw, &a, &ab_c));
+            assert!(!a.c_commands(&lw, &c, &ab_c));
         }
     }
 
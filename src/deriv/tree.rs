@@ -0,0 +1,275 @@
+use crate::prelude::*;
+
+use std::collections::{ HashMap };
+
+
+
+/// The id of a node in a [`Tree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+
+
+/// A node in a [`Tree`], i.e. one position of a syntactic object (see [`Path`]).
+#[derive(Debug)]
+struct Node<'a> {
+    so: &'a SyntacticObject,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    depth: usize,
+    /// The ids of the nodes that this node contains are exactly those in `(id, end)`, since the nodes are numbered in pre-order.
+    end: usize,
+    label: Option<LexicalItemToken>,
+}
+
+
+
+/// An immutable tree with parent pointers, built from a syntactic object.
+///
+/// Each node of the tree is one position of a syntactic object contained in the root, so the copies created by internal Merge are different nodes (cf. [`Occurrence`]). Once the tree is built, the following take constant time (or time linear in the number of sisters, which is at most one for binary branching syntactic objects):
+///
+/// *   sisterhood, containment and c-command,
+/// *   the label of a node, and whether it is a maximal, intermediate or minimal projection.
+///
+/// Looking up the nodes of a syntactic object takes constant time on average.
+#[derive(Debug)]
+pub struct Tree<'a> {
+    /// The nodes of the tree in pre-order, so that `nodes[0]` is the root.
+    nodes: Vec<Node<'a>>,
+    ids: HashMap<&'a SyntacticObject, Vec<NodeId>>,
+}
+
+
+
+impl<'a> Tree<'a> {
+    /// Build the tree of `so`, where `so` is contained in the workspace `w`.
+    ///
+    /// The labels of all the nodes are computed here with `triggers`, with respect to `w`.
+    pub fn new<T: Triggers>(triggers: &T, so: &'a SyntacticObject, w: &Workspace) -> Self {
+        let mut tree = Self { nodes: vec![], ids: HashMap::new() };
+        tree.add(triggers, so, None, 0, w);
+        tree
+    }
+
+    fn add<T: Triggers>(&mut self, triggers: &T, so: &'a SyntacticObject, parent: Option<NodeId>, depth: usize, w: &Workspace) -> NodeId {
+        let id = NodeId(self.nodes.len());
+
+        self.nodes.push(Node {
+            so,
+            parent,
+            children: vec![],
+            depth,
+            end: id.0 + 1,
+            label: triggers.label_of(so, w).ok(),
+        });
+        self.ids.entry(so).or_default().push(id);
+
+        let children: Vec<&'a SyntacticObject> = match so {
+            SyntacticObject::LexicalItemToken(_) => vec![],
+            SyntacticObject::Set(set) => set.iter().collect(),
+            SyntacticObject::Transfer { so, .. } => vec![ so ],
+        };
+
+        for child in children {
+            let child = self.add(triggers, child, Some(id), depth + 1, w);
+            self.nodes[id.0].children.push(child);
+        }
+
+        self.nodes[id.0].end = self.nodes.len();
+        id
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// The number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Return an iterator over all the nodes, in pre-order.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    pub fn so(&self, id: NodeId) -> &'a SyntacticObject {
+        self.nodes[id.0].so
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id.0].children
+    }
+
+    /// The depth of `id`, where the root has depth 0.
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.nodes[id.0].depth
+    }
+
+    /// Return the nodes of `so`, i.e. all of its positions in the root, in pre-order. If the root does not contain `so` (and is not `so`), there is none.
+    pub fn nodes_of(&self, so: &SyntacticObject) -> &[NodeId] {
+        self.ids.get(so).map_or(&[], |ids| ids.as_slice())
+    }
+
+    /// Return the position of `id`, from the root down to `id`.
+    pub fn path(&self, id: NodeId) -> Path<'a> {
+        let mut sos = vec![ self.so(id) ];
+        let mut id = id;
+        while let Some(parent) = self.parent(id) {
+            sos.push(self.so(parent));
+            id = parent;
+        }
+        sos.reverse();
+        Path::new(sos)
+    }
+
+    /// Immediate containment (for nodes).
+    ///
+    /// See [`Occurrence::immediately_contains`].
+    pub fn immediately_contains(&self, a: NodeId, b: NodeId) -> bool {
+        self.parent(b) == Some(a)
+    }
+
+    /// Containment (for nodes).
+    ///
+    /// See [`Occurrence::contains`].
+    pub fn contains(&self, a: NodeId, b: NodeId) -> bool {
+        (a.0 < b.0) && (b.0 < self.nodes[a.0].end)
+    }
+
+    /// Sisterhood (for nodes).
+    ///
+    /// See [`Occurrence::sisters_with`].
+    pub fn sisters_with(&self, a: NodeId, b: NodeId) -> bool {
+        (a != b) &&
+        self.parent(a).is_some() &&
+        (self.parent(a) == self.parent(b))
+    }
+
+    /// C-command (for nodes).
+    ///
+    /// See [`Occurrence::c_commands`].
+    pub fn c_commands(&self, a: NodeId, b: NodeId) -> bool {
+        match self.parent(a) {
+            Some(parent) =>
+                self.children(parent).iter()
+                    .filter(|&&c| c != a)
+                    .any(|&c| c == b || self.contains(c, b)),
+            None => false,
+        }
+    }
+
+    /// Asymmetric c-command (for nodes).
+    ///
+    /// See [`Occurrence::asymmetrically_c_commands`].
+    pub fn asymmetrically_c_commands(&self, a: NodeId, b: NodeId) -> bool {
+        (!self.sisters_with(a, b)) &&
+        self.c_commands(a, b)
+    }
+
+    /// The label of `id`, if it is defined.
+    ///
+    /// See [`Triggers::label_of`].
+    pub fn label(&self, id: NodeId) -> Option<&LexicalItemToken> {
+        self.nodes[id.0].label.as_ref()
+    }
+
+    /// See [`SO::is_maximal_projection_of`]. Here, only the parent of this particular node is taken into account.
+    pub fn is_maximal_projection_of(&self, id: NodeId, lit: &LexicalItemToken) -> bool {
+        (self.label(id) == Some(lit)) &&
+        (self.parent(id).and_then(|parent| self.label(parent)) != Some(lit))
+    }
+
+    /// See [`SO::is_minimal_projection`].
+    pub fn is_minimal_projection(&self, id: NodeId) -> bool {
+        self.so(id).is_minimal_projection()
+    }
+
+    /// See [`SO::is_intermediate_projection_of`].
+    pub fn is_intermediate_projection_of(&self, id: NodeId, lit: &LexicalItemToken) -> bool {
+        (!self.is_maximal_projection_of(id, lit)) &&
+        (!self.is_minimal_projection(id))
+    }
+
+    /// Check if no other node of the syntactic object of `id` is immediately contained in a node that contains the parent of `id`, i.e. if `id` is not a lower copy.
+    ///
+    /// See [`SO::is_final`].
+    pub fn is_final(&self, id: NodeId) -> bool {
+        match self.parent(id) {
+            Some(parent) =>
+                !self.nodes_of(self.so(id)).iter()
+                    .filter_map(|&other| self.parent(other))
+                    .any(|c| self.contains(c, parent)),
+            None => true,
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    mod tree {
+        use crate::prelude::*;
+        use crate::deriv::tree::{ Tree };
+
+
+
+        #[test]
+        fn copies() {
+            //  [ John [ was [ helped John ] ] ]
+            let john = lit!(li!("John"; "D"; "John"), 1);
+            let was = lit!(li!(; "T", "=V", "EPP"; "was"), 2);
+            let helped = lit!(li!("help"; "V", "=D"; "helped"), 3);
+
+            let vp = so!(so!(helped.clone()), so!(john.clone()),);
+            let t1 = so!(so!(was.clone()), vp.clone(),);
+            let tp = so!(so!(john.clone()), t1.clone(),);
+            let w = w!(tp.clone());
+
+            let tree = Tree::new(&BasicTriggers::default(), &tp, &w);
+            assert_eq!(tree.len(), 7);
+            assert_eq!(tree.so(tree.root()), &tp);
+
+            let johns = tree.nodes_of(&so!(john.clone()));
+            assert_eq!(johns.len(), 2);
+            let (upper, lower) =
+                if tree.depth(johns[0]) < tree.depth(johns[1]) { (johns[0], johns[1]) } else { (johns[1], johns[0]) };
+            let v = tree.nodes_of(&so!(helped.clone()))[0];
+            let vp = tree.nodes_of(&vp)[0];
+            let t1 = tree.nodes_of(&t1)[0];
+
+            //  The same relations as for occurrences.
+            assert_eq!(tree.path(lower), tp.paths_to(&so!(john.clone())).into_iter().max_by_key(|path| path.len()).unwrap());
+            assert!(tree.c_commands(upper, lower));
+            assert!(tree.asymmetrically_c_commands(upper, lower));
+            assert!(!tree.c_commands(lower, upper));
+            assert!(tree.sisters_with(lower, v));
+            assert!(!tree.asymmetrically_c_commands(lower, v));
+            assert!(tree.immediately_contains(vp, lower));
+            assert!(tree.contains(tree.root(), lower));
+            assert!(!tree.contains(vp, upper));
+            assert!(!tree.contains(vp, vp));
+
+            //  Only the upper copy is final.
+            assert!(tree.is_final(upper));
+            assert!(!tree.is_final(lower));
+
+            //  Projections.
+            assert_eq!(tree.label(t1), Some(&was));
+            assert_eq!(tree.label(tree.root()), Some(&was));
+            assert!(tree.is_maximal_projection_of(tree.root(), &was));
+            assert!(tree.is_intermediate_projection_of(t1, &was));
+            assert!(tree.is_maximal_projection_of(vp, &helped));
+            assert!(tree.is_minimal_projection(lower));
+        }
+    }
+}
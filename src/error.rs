@@ -0,0 +1,63 @@
+use crate::prelude::*;
+
+use std::fmt;
+
+
+
+/// An error in a syntactic operation or function, together with the syntactic object that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandsError<S: SO = SyntacticObject> {
+    /// This syntactic object is a set, but it does not have exactly two members.
+    NotBinary(S),
+    /// This syntactic object is not contained in the workspace, so its label is undefined.
+    NotInWorkspace(S),
+    /// This syntactic object is contained in the workspace, but it is not accessible under the PIC (see [`PicPolicy`]), so it cannot be labeled.
+    Inaccessible(S),
+    /// The triggers (and the label) of this syntactic object $\\{ B, C \\}$ are undefined, because both $\\textrm{Triggers} (B)$ and $\\textrm{Triggers} (C)$ are nonempty, i.e. both $B$ and $C$ would project.
+    BothProject(S),
+    /// The triggers (and the label) of this syntactic object $\\{ B, C \\}$ are undefined, because both $\\textrm{Triggers} (B)$ and $\\textrm{Triggers} (C)$ are empty, i.e. neither $B$ nor $C$ would project.
+    NeitherProjects(S),
+    /// The triggers of this syntactic object $\\{ B, C \\}$ are undefined, because $C$ cannot check any trigger feature of $B$, e.g. because $B$ selects a category that $C$ does not have.
+    NoCheckableTrigger(S),
+    /// Merge(A, B) is undefined, because A and B are this same syntactic object.
+    IdenticalOperands(S),
+    /// Merge(A, B) is undefined, because A, i.e. this syntactic object, has no trigger features.
+    NoTriggers(S),
+    /// Merge(A, B) is undefined, because B, i.e. this syntactic object, still has trigger features.
+    UncheckedTriggers(S),
+    /// Neither daughter of this syntactic object is the complement or the specifier of the other, so Transfer cannot linearize it.
+    Unordered(S),
+    /// This syntactic object contains no complement of its head that Cyclic-Transfer can transfer.
+    NoComplement(S),
+}
+
+
+
+impl<S: SO> fmt::Display for CandsError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CandsError::NotBinary(so) =>
+                write!(f, "This syntactic object must have exactly two members: {}", SOPrefixFormatter::new(so, 53)),
+            CandsError::NotInWorkspace(so) =>
+                write!(f, "This syntactic object is not in the workspace: {}", SOPrefixFormatter::new(so, 47)),
+            CandsError::Inaccessible(so) =>
+                write!(f, "This syntactic object is not accessible under the PIC: {}", SOPrefixFormatter::new(so, 55)),
+            CandsError::BothProject(so) =>
+                write!(f, "Both members of this syntactic object have trigger features: {}", SOPrefixFormatter::new(so, 61)),
+            CandsError::NeitherProjects(so) =>
+                write!(f, "Neither member of this syntactic object has trigger features: {}", SOPrefixFormatter::new(so, 62)),
+            CandsError::NoCheckableTrigger(so) =>
+                write!(f, "One member of this syntactic object can't check any trigger feature of the other: {}", SOPrefixFormatter::new(so, 82)),
+            CandsError::IdenticalOperands(so) =>
+                write!(f, "Can't merge this syntactic object with itself: {}", SOPrefixFormatter::new(so, 47)),
+            CandsError::NoTriggers(so) =>
+                write!(f, "For Merge(A, B), A must have trigger features, but A = {}", SOPrefixFormatter::new(so, 55)),
+            CandsError::UncheckedTriggers(so) =>
+                write!(f, "For Merge(A, B), B must have no trigger features, but B = {}", SOPrefixFormatter::new(so, 58)),
+            CandsError::Unordered(so) =>
+                write!(f, "Can't linearize this syntactic object: {}", SOPrefixFormatter::new(so, 39)),
+            CandsError::NoComplement(so) =>
+                write!(f, "This syntactic object has no complement that can be transferred: {}", SOPrefixFormatter::new(so, 65)),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::utils::{ Set, set };
+
 use std::fmt;
 
 
@@ -7,12 +9,21 @@ macro_rules! epp_feature { () => { f!("EPP") }; }
 macro_rules! comp_feature { () => { f!("C") } }
 macro_rules! strong_light_verb_feature { () => { f!("v*") } }
 
-pub(crate) use { wh_feature, epp_feature, comp_feature, strong_light_verb_feature };
+pub(crate) use { wh_feature, epp_feature };
 
 pub const CATSEL_FEATURE_PREFIX: &'static str = "=";
 
 
 
+/// The features of the heads of strong phases in C&S 2016, i.e. C and v*.
+///
+/// This is the default set of phase heads in [`crate::deriv::UniversalGrammar`].
+pub fn default_phase_heads() -> Set<Feature> {
+    set!( comp_feature!(), strong_light_verb_feature!() )
+}
+
+
+
 /// Features are identified by strings.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -70,6 +81,7 @@ macro_rules! fset {
     };
 }
 
+#[cfg(test)]
 pub(crate) use fset;
 
 
@@ -90,6 +102,7 @@ macro_rules! fvec {
     };
 }
 
+#[cfg(test)]
 pub(crate) use fvec;
 
 
@@ -0,0 +1,380 @@
+use crate::prelude::*;
+use crate::labels::{ Lookup };
+
+use std::borrow::{ Cow };
+use std::cell::{ RefCell };
+use std::collections::{ HashMap, HashSet };
+
+use itertools::{ Either };
+use once_cell::unsync::{ OnceCell };
+
+
+
+/// A memo from syntactic objects to the results of a function on them that may be undefined.
+type Memo<S, V> = RefCell<HashMap<S, Result<V, CandsError<S>>>>;
+
+
+
+/// The heads of the strong phases that a path from a root goes through, as far as PIC2 is concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PhasesAbove {
+    /// The path has not gone through a projection of a phase head.
+    None,
+    /// The path has only gone through projections of this phase head.
+    One(LexicalItemToken),
+    /// The path has gone through projections of more than one phase head.
+    Many,
+}
+
+
+
+impl PhasesAbove {
+    /// The phase heads after the path goes through a projection of `label`, if `label` is a phase head.
+    fn through(self, label: Option<LexicalItemToken>) -> Self {
+        match (self, label) {
+            (above, None) => above,
+            (PhasesAbove::None, Some(head)) => PhasesAbove::One(head),
+            (PhasesAbove::One(h), Some(head)) if h == head => PhasesAbove::One(h),
+            _ => PhasesAbove::Many,
+        }
+    }
+
+    /// Check if a syntactic object transferred in a projection of `label` is still accessible.
+    fn lets_through(&self, label: Option<&LexicalItemToken>) -> bool {
+        match self {
+            PhasesAbove::None => true,
+            PhasesAbove::One(h) => label == Some(h),
+            PhasesAbove::Many => false,
+        }
+    }
+}
+
+
+
+/// A workspace together with the labels and the trigger features of the syntactic objects in it.
+///
+/// Each label and each set of remaining trigger features is computed with [`Triggers`] at most once, when it is first asked for, and then shared by every check on the same workspace, e.g. by all the candidates for Merge(A, B) at one stage of a derivation.
+///
+/// The trigger features are those of a UG (see [`UniversalGrammar::triggers`]), and strong phases are maximal projections of its phase heads (see [`UniversalGrammar::phase_heads`]).
+/// Only the syntactic objects accessible under the PIC of the UG can be labeled (see [`LabeledWorkspace::label_of`]).
+pub struct LabeledWorkspace<'a, T: Triggers, S: SO = SyntacticObject> {
+    w: &'a Workspace<S>,
+    ug: Cow<'a, UniversalGrammar<T>>,
+    triggers: Memo<S, Set<Feature>>,
+    labels: Memo<S, LexicalItemToken>,
+    inaccessible: OnceCell<HashSet<&'a S>>,
+}
+
+
+
+impl<'a, T: Triggers, S: SO> LabeledWorkspace<'a, T, S> {
+    /// Make a labeled workspace with the default trigger features and phase heads (see [`default_phase_heads`]).
+    pub fn new(w: &'a Workspace<S>) -> Self {
+        Self::with_cow_ug(w, Cow::Owned(UniversalGrammar::new(set!(), set!(), set!())))
+    }
+
+    /// Make a labeled workspace with the trigger features and the phase heads of `ug`.
+    pub fn with_ug(ug: &'a UniversalGrammar<T>, w: &'a Workspace<S>) -> Self {
+        Self::with_cow_ug(w, Cow::Borrowed(ug))
+    }
+
+    fn with_cow_ug(w: &'a Workspace<S>, ug: Cow<'a, UniversalGrammar<T>>) -> Self {
+        Self {
+            w,
+            ug,
+            triggers: RefCell::new(HashMap::new()),
+            labels: RefCell::new(HashMap::new()),
+            inaccessible: OnceCell::new(),
+        }
+    }
+
+    pub fn workspace(&self) -> &'a Workspace<S> {
+        self.w
+    }
+
+    /// The UG whose trigger features, phase heads and policies are used.
+    pub fn ug(&self) -> &UniversalGrammar<T> {
+        &self.ug
+    }
+
+    /// The features of the heads of strong phases.
+    pub fn phase_heads(&self) -> &Set<Feature> {
+        &self.ug.phase_heads
+    }
+
+    /// Return the syntactic objects contained in `so` that are accessible under the PIC of UG (see [`UniversalGrammar::pic_policy`]), starting with `so` itself if `start_with_self`.
+    pub fn accessible_sos<'b>(&self, so: &'b S, start_with_self: bool) -> impl Iterator<Item = &'b S> {
+        let mut it = self.accessible_from(vec![ (so, PhasesAbove::None, true) ]);
+        if !start_with_self {
+            it.next();
+        }
+        it
+    }
+
+    /// Return the syntactic objects contained in the workspace that are accessible under the PIC of UG. See [`LabeledWorkspace::accessible_sos`].
+    pub fn accessible_in_workspace(&self) -> impl Iterator<Item = &'a S> {
+        self.accessible_from(
+            self.w.iter()
+                .map(|so| (so, PhasesAbove::None, true))
+                .collect()
+        )
+    }
+
+    /// Check if `so` is contained in the workspace, but not accessible under the PIC of UG.
+    fn is_inaccessible(&self, so: &S) -> bool {
+        self.inaccessible
+            .get_or_init(|| {
+                let accessible: HashSet<_> = self.accessible_in_workspace().collect();
+                self.w.contained_sos(false)
+                    .filter(|so| !accessible.contains(so))
+                    .collect()
+            })
+            .contains(so)
+    }
+
+    /// Return the label of `so` if it is a phase head.
+    fn phase_head_of(&self, so: &S) -> Option<LexicalItemToken> {
+        self.label(so).ok()
+            .filter(|label| !label.li.syn.is_disjoint(self.phase_heads()))
+    }
+
+    /// Start from each root with the phase heads above it and whether it may be looked inside if it is transferred.
+    fn accessible_from<'b>(&self, roots: Vec<(&'b S, PhasesAbove, bool)>) -> Either<ContainedSyntacticObjects<'b, S>, std::vec::IntoIter<&'b S>> {
+        let pic_compliant = match self.ug.pic_policy {
+            PicPolicy::None => false,
+            PicPolicy::Pic1 => true,
+            PicPolicy::Pic2 => {
+                return Either::Right(self.pic2_accessible_from(roots).into_iter());
+            },
+        };
+
+        let stack = roots.into_iter()
+            .map(|(so, _, _)| so)
+            .collect();
+        Either::Left(ContainedSyntacticObjects::new(stack, pic_compliant))
+    }
+
+    /// Visit the syntactic objects in the same order as [`ContainedSyntacticObjects`], but only look inside a transferred syntactic object if no phase head other than the one it was transferred in has been merged above it.
+    fn pic2_accessible_from<'b>(&self, roots: Vec<(&'b S, PhasesAbove, bool)>) -> Vec<&'b S> {
+        //  Each syntactic object to visit, with the phase heads above it and whether it is open, i.e. may be looked inside if it is transferred.
+        let mut stack = roots;
+        let mut res = vec![];
+
+        while let Some((so, above, open)) = stack.pop() {
+            res.push(so);
+
+            match so.view() {
+                SOView::Set(children) => {
+                    let label = self.label(so).ok();
+                    let above = above.through(self.phase_head_of(so));
+                    let open = above.lets_through(label.as_ref());
+
+                    for child in children {
+                        stack.push((child, above.clone(), open));
+                    }
+                },
+                SOView::Transfer { so, .. } if open => {
+                    stack.push((so, above, true));
+                },
+                _ => (),
+            }
+        }
+
+        res
+    }
+
+    /// Return the remaining trigger features of `so`. See [`Triggers::triggers`].
+    pub fn triggers(&self, so: &S) -> Result<Set<Feature>, CandsError<S>> {
+        if let Some(tfs) = self.triggers.borrow().get(so) {
+            return tfs.clone();
+        }
+
+        let tfs = self.ug.triggers.triggers_in(so, self);
+        self.triggers.borrow_mut().insert(so.clone(), tfs.clone());
+        tfs
+    }
+
+    /// Return the label of `so`, if `so` is not contained in the workspace or is accessible under the PIC of UG (see [`UniversalGrammar::pic_policy`]). See [`Triggers::label_of`].
+    pub fn label_of(&self, so: &S) -> Result<LexicalItemToken, CandsError<S>> {
+        if self.is_inaccessible(so) {
+            return Err(CandsError::Inaccessible(so.clone()));
+        }
+
+        self.label(so)
+    }
+
+    /// Return the label of `so` regardless of the PIC.
+    fn label(&self, so: &S) -> Result<LexicalItemToken, CandsError<S>> {
+        if let Some(label) = self.labels.borrow().get(so) {
+            return label.clone();
+        }
+
+        let label = self.ug.triggers.label_in(so, self);
+        self.labels.borrow_mut().insert(so.clone(), label.clone());
+        label
+    }
+
+    /// See [`SO::is_maximal_projection_of`].
+    pub fn is_maximal_projection_of(&self, so: &S, lit: &LexicalItemToken) -> bool {
+        match self.label(so) {
+            Ok(label) => {
+                (&label == lit) &&
+                (!self.w.contained_sos(false)
+                    .any(|d| {
+                        d.immediately_contains(so) &&
+                        (self.label(d).as_ref() == Ok(&label))
+                    }))
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// See [`SO::is_complement_of`].
+    pub fn is_complement_of(&self, so: &S, head: &S, under: &S) -> bool {
+        match self.triggered_merge(head.clone(), so.clone()) {
+            Ok(merged) => {
+                (under == &merged) && head.is_lexical_item_token()
+            },
+            _ => false,
+        }
+    }
+
+    /// See [`SO::is_specifier_of`].
+    pub fn is_specifier_of(&self, so: &S, head: &S, under: &S) -> bool {
+        match self.triggered_merge(head.clone(), so.clone()) {
+            Ok(merged) => {
+                (under == &merged) && head.is_set()
+            },
+            _ => false,
+        }
+    }
+
+    /// C-command, where only the syntactic objects accessible under the PIC of UG can be c-commanded, as in [`LabeledWorkspace::label_of`]. See [`SO::c_commands`].
+    pub fn c_commands(&self, so: &S, other: &S, under: &S) -> bool {
+        if self.is_inaccessible(other) {
+            return false;
+        }
+
+        match under.view() {
+            SOView::Set(children) => children.iter()
+                .filter(|&c| c.sisters_with(so, under))
+                .any(|c| c == other || c.contains(other)),
+            _ => false,
+        }
+    }
+}
+
+
+
+impl<'a, T: Triggers, S: SO> Lookup<S> for LabeledWorkspace<'a, T, S> {
+    fn workspace(&self) -> &Workspace<S> {
+        self.w
+    }
+
+    fn triggers(&self, so: &S) -> Result<Set<Feature>, CandsError<S>> {
+        LabeledWorkspace::triggers(self, so)
+    }
+
+    fn label(&self, so: &S) -> Result<LexicalItemToken, CandsError<S>> {
+        LabeledWorkspace::label(self, so)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    mod labeled {
+        use crate::prelude::*;
+        use crate::deriv::{ DerivationError, check_derivation };
+        use crate::tests::fixtures::{ init, mary_helped_john };
+
+
+
+        #[test]
+        fn same_as_triggers() {
+            let john = lit!(li!("John"; "D"; "John"), 1);
+            let helped = lit!(li!("help"; "V", "=D"; "helped"), 3);
+            let vp = so!(so!(helped.clone()), so!(john.clone()),);
+            let w = w!(vp.clone());
+            let lw = LabeledWorkspace::<BasicTriggers>::new(&w);
+
+            for so in w.contained_sos(false) {
+                //  Ask twice, so that the second answer comes from the memo.
+                for _ in 0..2 {
+                    assert_eq!(lw.triggers(so), BasicTriggers::default().triggers(so, &w));
+                    assert_eq!(lw.label_of(so), BasicTriggers::default().label_of(so, &w));
+                }
+            }
+
+            assert_eq!(lw.label_of(&vp), Ok(helped.clone()));
+            assert!(lw.is_maximal_projection_of(&vp, &helped));
+            assert!(!lw.is_maximal_projection_of(&so!(helped.clone()), &helped));
+            assert!(lw.is_complement_of(&so!(john), &so!(helped), &vp));
+        }
+
+
+
+        #[test]
+        fn pic_policy() {
+            init();
+
+            let (mut il, stages) = mary_helped_john();
+
+            let helped = lit!(li!("help"; "V", "=D"; "helped"), 2);
+            let pres = lit!(li!("PRES"; "T", "=v*";), 4);
+            let c = lit!(li!("C"; "C", "=T", "EPP";), 5);
+            let john = lit!(li!("John"; "D"; "John"), 6);
+
+            let vp = so!(so!(helped), so!(john.clone()),);
+            let tp = stages[10].w.iter().next().unwrap().clone();
+            let cp = stages[12].w.iter().next().unwrap().clone();
+            //  Moving "John" out of the transferred VP needs a UG without PIC.
+            let mv = Operation::Merge(cp.clone(), so!(john.clone()));
+            assert!(stages[12].apply(&il.ug, &mv).is_err());
+            assert_eq!(stages.len(), 14);
+
+            let w_tp = &stages[10].w;
+            let w_cp = &stages[12].w;
+
+            //  Whether the VP can be labeled, and whether "John" in it is c-commanded, in the TP and in the CP.
+            let check = |ug: &UniversalGrammar<BasicTriggers>| {
+                let lw_tp = LabeledWorkspace::with_ug(ug, w_tp);
+                let lw_cp = LabeledWorkspace::with_ug(ug, w_cp);
+                let label_in_tp = lw_tp.label_of(&vp).is_ok();
+                let label_in_cp = lw_cp.label_of(&vp).is_ok();
+                let by_pres = so!(pres.clone()).c_commands(&lw_tp, &so!(john.clone()), &tp);
+                let by_c = so!(c.clone()).c_commands(&lw_cp, &so!(john.clone()), &cp);
+                (label_in_tp, label_in_cp, by_pres, by_c)
+            };
+
+            //  PIC1: the VP is inaccessible as soon as it is transferred.
+            assert_eq!(il.ug.pic_policy, PicPolicy::Pic1);
+            assert_eq!(check(&il.ug), (false, false, false, false));
+            assert_eq!(
+                LabeledWorkspace::with_ug(&il.ug, w_tp).label_of(&vp),
+                Err(CandsError::Inaccessible(vp.clone()))
+            );
+            assert_eq!(
+                check_derivation(&il, &stages),
+                Err(DerivationError::NoMatchingRule { stage: 13, reason: None })
+            );
+
+            //  PIC2: the VP is accessible to T, but not once C is merged.
+            il.ug.pic_policy = PicPolicy::Pic2;
+            assert_eq!(check(&il.ug), (true, false, true, false));
+            //  Not even to T inside the TP, since the VP cannot be labeled there either.
+            assert!(!so!(pres.clone()).c_commands(&LabeledWorkspace::with_ug(&il.ug, w_cp), &so!(john.clone()), &tp));
+            assert_eq!(
+                check_derivation(&il, &stages),
+                Err(DerivationError::NoMatchingRule { stage: 13, reason: None })
+            );
+            assert!(is_derivation(&il, &stages[..13]));
+
+            //  No PIC: the VP is always accessible.
+            il.ug.pic_policy = PicPolicy::None;
+            assert_eq!(check(&il.ug), (true, true, true, true));
+            assert!(is_derivation(&il, &stages));
+        }
+    }
+}
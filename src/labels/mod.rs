@@ -1,3 +1,8 @@
+pub mod labeled;
+
+
+
+use std::fmt;
 use std::ops::{ Deref };
 
 use crate::prelude::*;
@@ -5,7 +10,9 @@ use crate::prelude::*;
 
 
 /// A trait for trigger features, labels, etc.
-pub trait Triggers {
+///
+/// An instance of this trait is part of UG (see [`UniversalGrammar::triggers`]). Where there is no UG, the default instance is used.
+pub trait Triggers: Clone + Default {
     /// Triggers.
     /// 
     /// From Definition 26 in C&S 2016, p. 63:
@@ -15,7 +22,14 @@ pub trait Triggers {
     /// >1.  If $A$ is a lexical item token with $n$ trigger features, then $\\textrm{Triggers} (A)$ returns all of those $n$ trigger features.
     /// >2.  If $A$ is a set, then $A = \\{ B, C \\}$ where $\\textrm{Triggers} (B)$ is nonempty, and $\\textrm{Triggers} (C) = \\varnothing$, and $\\textrm{Triggers} (A) = \\textrm{Triggers} (B) \setminus \\{ \\textrm{TF} \\}$, for some trigger feature $\\textrm{TF} \\in \\textrm{Triggers} B)$.
     /// >3.  Otherwise, $\\textrm{Triggers} (A)$ is undefined.
-    fn triggers(so: &SyntacticObject, w: &Workspace) -> Result<Set<Feature>, ()>;
+    ///
+    /// Where $\\textrm{Triggers} (A)$ is undefined, return an error with the offending syntactic object.
+    fn triggers<S: SO>(&self, so: &S, w: &Workspace<S>) -> Result<Set<Feature>, CandsError<S>> {
+        self.triggers_in(so, &Direct { triggers: self, w })
+    }
+
+    /// See [`Triggers::triggers`]. The trigger features and the labels of the syntactic objects contained in `so` are looked up in `lookup`.
+    fn triggers_in<S: SO, L: Lookup<S>>(&self, so: &S, lookup: &L) -> Result<Set<Feature>, CandsError<S>>;
 
     /// Label.
     /// 
@@ -25,15 +39,24 @@ pub trait Triggers {
     /// >
     /// >1.  For all lexical item tokens LI, Label(LI) = LI.
     /// >2.  Let W be a derivable workspace. If {A, B} is contained in W, and Triggers (A) is nonempty, then Label({A, B}) = Label(A).
-    fn label_of<'a>(so: &'a SyntacticObject, w: &Workspace) -> Result<&'a LexicalItemToken, ()> {
+    ///
+    /// Where the label is undefined, return an error with the offending syntactic object.
+    ///
+    /// This ignores the PIC. To label only the syntactic objects accessible under the PIC of a UG, use [`LabeledWorkspace::label_of`].
+    fn label_of<S: SO>(&self, so: &S, w: &Workspace<S>) -> Result<LexicalItemToken, CandsError<S>> {
+        self.label_in(so, &Direct { triggers: self, w })
+    }
+
+    /// See [`Triggers::label_of`]. The trigger features and the labels of the syntactic objects contained in `so` are looked up in `lookup`.
+    fn label_in<S: SO, L: Lookup<S>>(&self, so: &S, lookup: &L) -> Result<LexicalItemToken, CandsError<S>> {
         // eprintln!("Label: so =\n{}", so);
 
-        match so {
-            &SyntacticObject::LexicalItemToken(ref lit) => Ok(lit),
-            &SyntacticObject::Set(ref set) => {
-                if !w.contains(so) {
+        match so.view() {
+            SOView::LexicalItemToken(lit) => Ok(lit.clone()),
+            SOView::Set(set) => {
+                if !lookup.workspace().contains(so) {
                     // eprintln!("Label: The workspace does not contain so. W =\n{}", w);
-                    return Err(());
+                    return Err(CandsError::NotInWorkspace(so.clone()));
                 }
 
                 match set.len() == 2 {
@@ -41,150 +64,345 @@ pub trait Triggers {
                         let mut iter = set.iter();
                         let b = iter.next().unwrap();
                         let c = iter.next().unwrap();
-                        assert!(iter.next().is_none());
 
                         // eprintln!("Label: so = {{ B, C }}, where B =\n{}", b);
                         // eprintln!("Label: C = \n{}", c);
                         
-                        let tfs_b = Self::triggers(b, w)?;
+                        let tfs_b = lookup.triggers(b)?;
 
                         // eprintln!("Label: Triggers(B) = {:?}", tfs_b);
 
-                        let tfs_c = Self::triggers(c, w)?;
+                        let tfs_c = lookup.triggers(c)?;
 
                         // eprintln!("Label: Triggers(C) = {:?}", tfs_c);
 
                         if !tfs_b.is_empty() && tfs_c.is_empty() {
                             // eprintln!("Label: Triggers(B) != ∅, Triggers(C) == ∅");
-                            Self::label_of(b, w)
+                            lookup.label(b)
                         }
                         else if !tfs_c.is_empty() && tfs_b.is_empty() {
                             // eprintln!("Label: Triggers(C) != ∅, Triggers(B) == ∅");
-                            Self::label_of(c, w)
+                            lookup.label(c)
                         }
                         else {
                             // eprintln!("Label: Error. Triggers(B) = {:?}, Triggers(C) = {:?}", tfs_b, tfs_c);
-                            Err(())
+                            if tfs_b.is_empty() {
+                                Err(CandsError::NeitherProjects(so.clone()))
+                            }
+                            else {
+                                Err(CandsError::BothProject(so.clone()))
+                            }
                         }
                     },
-                    false => Err(()),
+                    false => Err(CandsError::NotBinary(so.clone())),
                 }
             },
-            &SyntacticObject::Transfer{ ref so, .. } =>
-                Self::label_of(so, w),
+            SOView::Transfer { so, .. } =>
+                lookup.label(so),
         }
     }
+
+    /// Look for likely mistakes in the trigger features with respect to the syntactic features `syn_f` of UG.
+    ///
+    /// Return no warnings by default.
+    fn validate(&self, _syn_f: &Set<Feature>) -> Vec<TriggersWarning> {
+        vec![]
+    }
+}
+
+
+
+/// Where [`Triggers`] looks up the trigger features and the labels of the syntactic objects contained in the one it computes them for.
+///
+/// A [`LabeledWorkspace`] looks them up in its memo, so that each of them is computed at most once per workspace.
+pub trait Lookup<S: SO> {
+    /// The workspace that contains the syntactic objects.
+    fn workspace(&self) -> &Workspace<S>;
+
+    /// Return the remaining trigger features of `so`. See [`Triggers::triggers`].
+    fn triggers(&self, so: &S) -> Result<Set<Feature>, CandsError<S>>;
+
+    /// Return the label of `so`, regardless of the PIC. See [`Triggers::label_of`].
+    fn label(&self, so: &S) -> Result<LexicalItemToken, CandsError<S>>;
+}
+
+
+
+/// Look up the trigger features and the labels by computing them again every time.
+struct Direct<'a, T: Triggers, S: SO> {
+    triggers: &'a T,
+    w: &'a Workspace<S>,
+}
+
+
+
+impl<'a, T: Triggers, S: SO> Lookup<S> for Direct<'a, T, S> {
+    fn workspace(&self) -> &Workspace<S> {
+        self.w
+    }
+
+    fn triggers(&self, so: &S) -> Result<Set<Feature>, CandsError<S>> {
+        self.triggers.triggers_in(so, self)
+    }
+
+    fn label(&self, so: &S) -> Result<LexicalItemToken, CandsError<S>> {
+        self.triggers.label_in(so, self)
+    }
 }
 
 
 
-#[derive(Debug)]
+/// The trigger features of C&S 2016, with configurable special features.
+///
+/// There are three kinds of trigger features:
+///
+/// *   Probe features, like wh, are checked by a syntactic object with the same feature.
+/// *   Category selection features, like =V, are checked by a syntactic object with the selected category. They consist of a selector prefix, like =, followed by the category.
+/// *   EPP-like features, like EPP, are checked by any syntactic object.
+///
+/// The default is wh as the only probe feature, EPP as the only EPP-like feature and = as the selector prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct BasicTriggers;
+pub struct BasicTriggers {
+    probes: Set<Feature>,
+    epps: Set<Feature>,
+    selector_prefix: String,
+}
+
+
+
+impl Default for BasicTriggers {
+    fn default() -> Self {
+        Self {
+            probes: set!( wh_feature!() ),
+            epps: set!( epp_feature!() ),
+            selector_prefix: CATSEL_FEATURE_PREFIX.to_string(),
+        }
+    }
+}
+
+
+
+/// The reason why a configuration of trigger features is invalid. See [`BasicTriggers::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriggersError {
+    /// The selector prefix is empty, so every feature would select a category.
+    EmptySelectorPrefix,
+    /// The feature is both a probe feature and an EPP-like feature.
+    ProbeAndEpp(Feature),
+    /// The probe or EPP-like feature starts with the selector prefix, so it would also select a category.
+    StartsWithSelectorPrefix(Feature),
+}
+
+
+
+impl fmt::Display for TriggersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriggersError::EmptySelectorPrefix =>
+                write!(f, "The selector prefix must not be empty."),
+            TriggersError::ProbeAndEpp(feature) =>
+                write!(f, "The feature {} cannot be both a probe feature and an EPP-like feature.", feature),
+            TriggersError::StartsWithSelectorPrefix(feature) =>
+                write!(f, "The probe or EPP-like feature {} cannot start with the selector prefix.", feature),
+        }
+    }
+}
+
+
+
+/// A possible mistake in the trigger features with respect to UG. See [`Triggers::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriggersWarning {
+    /// The trigger feature is not in SYN-F, so no lexical item can have it.
+    Undeclared(Feature),
+    /// The feature in SYN-F is not a trigger feature, but looks like a misspelling of the trigger feature `trigger`, e.g. "epp" for "EPP".
+    Misspelled { feature: Feature, trigger: Feature },
+}
+
+
+
+impl fmt::Display for TriggersWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriggersWarning::Undeclared(feature) =>
+                write!(f, "The trigger feature {} is not in SYN-F.", feature),
+            TriggersWarning::Misspelled { feature, trigger } =>
+                write!(f, "The feature {} is not a trigger feature. Did you mean {}?", feature, trigger),
+        }
+    }
+}
 
 
 
 impl BasicTriggers {
-    /// Check one feature.
-    fn check_tf(mut from: Set<Feature>, wrt: &SyntacticObject, w: &Workspace) -> Result<Set<Feature>, ()> {
+    /// Make a configuration with the probe features `probes`, the EPP-like features `epps` and the selector prefix `selector_prefix`.
+    ///
+    /// Return an error if a feature would be a trigger feature of more than one kind.
+    pub fn new(probes: Set<Feature>, epps: Set<Feature>, selector_prefix: String) -> Result<Self, TriggersError> {
+        if selector_prefix.is_empty() {
+            return Err(TriggersError::EmptySelectorPrefix);
+        }
+
+        if let Some(feature) = probes.intersection(&epps).next() {
+            return Err(TriggersError::ProbeAndEpp(feature.clone()));
+        }
+
+        if let Some(feature) = probes.iter().chain(epps.iter()).find(|f| f.0.starts_with(&selector_prefix)) {
+            return Err(TriggersError::StartsWithSelectorPrefix(feature.clone()));
+        }
+
+        Ok(Self { probes, epps, selector_prefix })
+    }
+
+    pub fn probes(&self) -> &Set<Feature> {
+        &self.probes
+    }
+
+    pub fn epps(&self) -> &Set<Feature> {
+        &self.epps
+    }
+
+    pub fn selector_prefix(&self) -> &str {
+        &self.selector_prefix
+    }
+
+    /// Return the category that `f` selects, if `f` is a category selection feature. If `f` were "=v*", this would be "v*".
+    fn selected_category(&self, f: &Feature) -> Option<Feature> {
+        f.0.strip_prefix(self.selector_prefix.as_str())
+            .map(|cat| f!(cat))
+    }
+
+    /// Check if `f` is a trigger feature.
+    pub fn is_trigger(&self, f: &Feature) -> bool {
+        self.probes.contains(f) ||
+        self.epps.contains(f) ||
+        self.selected_category(f).is_some()
+    }
+
+    /// Check one feature of `from` with respect to `wrt`, where `wrt` and the syntactic object with the trigger features `from` are the members of `under`.
+    fn check_tf<S: SO, L: Lookup<S>>(&self, mut from: Set<Feature>, wrt: &S, under: &S, lookup: &L) -> Result<Set<Feature>, CandsError<S>> {
         //  Get the syntactic features of the label of `wrt`
-        let wrt_syn = &Self::label_of(wrt, w)?.li.syn;
+        let wrt_syn = &lookup.label(wrt)?.li.syn;
         // eprintln!("Check-TF: wrt_syn = {:?}", wrt_syn);
 
-        //  Check wh
-        if from.contains(&wh_feature!()) && wrt_syn.contains(&wh_feature!()) {
-            assert!(from.remove(&wh_feature!()));
+        //  Check probes, like wh
+        if let Some(probe) = from.iter()
+            .find(|&f| self.probes.contains(f) && wrt_syn.contains(f))
+            .cloned()
+        {
+            from.remove(&probe);
             return Ok(from);
         }
 
         //  Check category selection
         if let Some(catsel_feature) = from.iter()
             .find(|&f| {
-                //  If `f` were "=v*", then `cat_feature` would be "v*".
-                let cat_feature = f!(&f.0[CATSEL_FEATURE_PREFIX.len()..]);
-                f.0.starts_with(CATSEL_FEATURE_PREFIX) &&
-                (wrt_syn.contains(&cat_feature))
+                self.selected_category(f)
+                    .is_some_and(|cat_feature| wrt_syn.contains(&cat_feature))
             })
             .map(|f| f.clone()) // borrowck wins
         {
-            assert!(from.remove(&catsel_feature));
+            from.remove(&catsel_feature);
             return Ok(from);
         }
 
-        //  Check EPP
-        if from.contains(&epp_feature!()) {
-            assert!(from.remove(&epp_feature!()));
+        //  Check EPP-like features
+        if let Some(epp) = from.iter()
+            .find(|&f| self.epps.contains(f))
+            .cloned()
+        {
+            from.remove(&epp);
             return Ok(from);
         }
 
-        Err(())
+        Err(CandsError::NoCheckableTrigger(under.clone()))
     }
 }
 
 
 
 impl Triggers for BasicTriggers {
-    fn triggers(so: &SyntacticObject, w: &Workspace) -> Result<Set<Feature>, ()> {
+    fn triggers_in<S: SO, L: Lookup<S>>(&self, so: &S, lookup: &L) -> Result<Set<Feature>, CandsError<S>> {
         // eprintln!("Triggers: so =\n{}", so);
 
-        match so {
-            &SyntacticObject::LexicalItemToken(ref lit) => {
+        match so.view() {
+            SOView::LexicalItemToken(lit) => {
                 Ok(
                     lit.li.syn.iter()
-                        .filter(|&f| {
-                            f == &wh_feature!() ||
-                            f == &epp_feature!() ||
-                            f.0.starts_with(CATSEL_FEATURE_PREFIX)
-                        })
+                        .filter(|&f| self.is_trigger(f))
                         .cloned()
                         .collect()
                 )
                 //  Ok(lit.li.syn.intersection(&self.0).cloned().collect())
             },
-            &SyntacticObject::Set(ref set) => {
+            SOView::Set(set) => {
                 match set.len() == 2 {
                     true => {
                         let mut iter = set.iter();
                         let b = iter.next().unwrap();
                         let c = iter.next().unwrap();
-                        assert!(iter.next().is_none());
 
                         // eprintln!("Triggers: so = {{ B, C }}, where B =\n{}", b);
                         // eprintln!("Triggers: C = \n{}", c);
                         
-                        let tfs_b = Self::triggers(b, w)?;
+                        let tfs_b = lookup.triggers(b)?;
 
                         // eprintln!("Triggers: Triggers(B) = {:?}", tfs_b);
 
-                        let tfs_c = Self::triggers(c, w)?;
+                        let tfs_c = lookup.triggers(c)?;
 
                         // eprintln!("Triggers: Triggers(C) = {:?}", tfs_c);
 
                         if !tfs_b.is_empty() && tfs_c.is_empty() {
                             // eprintln!("Triggers: Triggers(B) != ∅, Triggers(C) == ∅");
-                            let res = Self::check_tf(tfs_b, c, w);
+                            let res = self.check_tf(tfs_b, c, so, lookup);
                             // eprintln!("Triggers: Triggers(so) = {:?}", res);
                             res
                         }
                         else if !tfs_c.is_empty() && tfs_b.is_empty() {
                             // eprintln!("Triggers: Triggers(C) != ∅, Triggers(B) == ∅");
-                            let res = Self::check_tf(tfs_c, b, w);
+                            let res = self.check_tf(tfs_c, b, so, lookup);
                             // eprintln!("Triggers: Triggers(so) = {:?}", res);
                             res
                         }
                         else {
                             // eprintln!("Triggers: Either B or C has to be empty, but Triggers(B) = {:?}, Triggers(C) = {:?}", tfs_b, tfs_c);
-                            Err(())
+                            if tfs_b.is_empty() {
+                                Err(CandsError::NeitherProjects(so.clone()))
+                            }
+                            else {
+                                Err(CandsError::BothProject(so.clone()))
+                            }
                         }
                     },
                     false => {
                         // eprintln!("Triggers: so needs to be a binary tree");
-                        Err(())
+                        Err(CandsError::NotBinary(so.clone()))
                     },
                 }
             },
-            &SyntacticObject::Transfer{ ref so, .. } =>
-                Self::triggers(&so, w),
+            SOView::Transfer { so, .. } =>
+                lookup.triggers(so),
         }
     }
+
+    /// Warn about probe and EPP-like features not in `syn_f`, and about features in `syn_f` that differ from one of them only in case.
+    fn validate(&self, syn_f: &Set<Feature>) -> Vec<TriggersWarning> {
+        let specials = || self.probes.iter().chain(self.epps.iter());
+
+        let undeclared = specials()
+            .filter(|&trigger| !syn_f.contains(trigger))
+            .map(|trigger| TriggersWarning::Undeclared(trigger.clone()));
+
+        let misspelled = syn_f.iter()
+            .filter(|&f| !self.is_trigger(f))
+            .filter_map(|f| {
+                specials()
+                    .find(|&trigger| trigger.0.eq_ignore_ascii_case(&f.0))
+                    .map(|trigger| TriggersWarning::Misspelled { feature: f.clone(), trigger: trigger.clone() })
+            });
+
+        undeclared.chain(misspelled).collect()
+    }
 }
\ No newline at end of file
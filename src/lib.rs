@@ -28,12 +28,14 @@
 //! 7.  syntactic object (??)
 //!
 //!     See [`SyntacticObject`].
+//!
+//!     The relations between syntactic objects are defined once for any representation of syntactic objects. See [`SO`], and [`InternedSO`] for a representation where equality takes constant time.
 //! 8.  immediate contaiment (of syntactic objects)
 //!
-//!     See [`SyntacticObject::immediately_contains`] and [`Workspace::immediately_contains`].
+//!     See [`SO::immediately_contains`] and [`Workspace::immediately_contains`].
 //! 9.  containment (of syntactic objects)
 //!
-//!     See [`SyntacticObject::contains`] and [`Workspace::contains`].
+//!     See [`SO::contains`] and [`Workspace::contains`].
 //! 
 //! ## Workspaces, Select and Merge
 //! 
@@ -42,7 +44,7 @@
 //!     See [`Stage`] and [`Workspace`].
 //! 11. root
 //!
-//!     See [`SyntacticObject::is_root`].
+//!     See [`SO::is_root`].
 //! 12. Select
 //!
 //!     See [`BasicSelect::select`].
@@ -51,18 +53,20 @@
 //!     See [`TokenBasedMerge::merge`].
 //! 14. derivation
 //!
-//!     See [`is_derivation`].
+//!     See [`is_derivation`] (and [`check_derivation`]).
+//!
+//!     [`check_derivation`] also tells internal and external Merge apart. See [`MergeKind`].
 //!
-//!     (internal vs. external Merge?)
+//!     [`check_derivation_parallel`] checks the pairs of stages on several threads at once.
 //! 15. derivability from lexicon
 //!
-//!     This definition does not have an implementation on its own.
+//!     See [`is_derivable`].
 //! 
 //! ## Occurrences
 //! 
 //! 16. position and path
 //! 
-//!     See [`Occurrence::check`].
+//!     See [`Path`] (and [`SyntacticObject::paths_to`]).
 //! 17. occurrence
 //! 
 //!     See [`Occurrence`].
@@ -71,13 +75,13 @@
 //!     See [`Occurrence::immediately_contains`] (and [`Occurrence::contains`]).
 //! 19. sisterhood (for syntactic objects)
 //! 
-//!     See [`SyntacticObject::sisters_with`].
+//!     See [`SO::sisters_with`].
 //! 20. sisterhood (for occurrences)
 //! 
 //!     See [`Occurrence::sisters_with`].
 //! 21. c-command and asymmetric c-command (for syntactic objects)
 //! 
-//!     See [`SyntacticObject::c_commands`] and [`SyntacticObject::asymmetrically_c_commands`].
+//!     See [`SO::c_commands`] and [`SO::asymmetrically_c_commands`].
 //! 22. c-command (for occurrences)
 //! 
 //!     See [`Occurrence::c_commands`] (and [`Occurrence::asymmetrically_c_commands`]).
@@ -86,20 +90,22 @@
 //! 
 //! 23. derivability
 //!
-//!     This definition does not have an implementation on its own.
+//!     See [`is_derivable`].
 //! 24. binary branching
 //! 
-//!     See [`SyntacticObject::is_binary_branching`].
+//!     See [`SO::is_binary_branching`].
 
 
 
 pub mod utils;
+pub mod error;
 pub mod feature;
 pub mod ops;
 pub mod deriv;
 pub mod prelude;
 pub mod labels;
 pub mod conv;
+pub mod spellout;
 // mod cli;
 
 
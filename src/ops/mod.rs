@@ -1,10 +1,81 @@
 use std::marker::{ PhantomData };
 use std::iter;
+use std::fmt;
 
 use crate::prelude::*;
 
 
 
+/// A syntactic operation, together with its operands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operation<S: SO = SyntacticObject> {
+    /// Select a lexical item token from the lexical array.
+    Select(LexicalItemToken),
+    /// Merge(A, B).
+    Merge(S, S),
+    /// Transfer(SO, SO), where SO is a strong phase.
+    Transfer(S),
+    /// Cyclic-Transfer(SO), where SO is a strong phase.
+    CyclicTransfer(S),
+}
+
+
+
+impl<S: SO> fmt::Display for Operation<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Select(lit) =>
+                write!(f, "Select({})", lit),
+            Operation::Merge(a, b) =>
+                write!(f, "Merge(A, B),\nwhere A = {}\n  and B = {}", SOPrefixFormatter::new(a, 10), SOPrefixFormatter::new(b, 10)),
+            Operation::Transfer(so) =>
+                write!(f, "Transfer(SO, SO),\nwhere SO = {}", SOPrefixFormatter::new(so, 11)),
+            Operation::CyclicTransfer(so) =>
+                write!(f, "Cyclic-Transfer(SO),\nwhere SO = {}", SOPrefixFormatter::new(so, 11)),
+        }
+    }
+}
+
+
+
+/// The two cases of Merge in Derive-by-Merge (see [`check_derivation`](crate::deriv::check_derivation)).
+///
+/// In Merge(A, B), where A is a root in the workspace, B is either a root in the workspace as well, or contained in A. The former is *external Merge*, and the latter is *internal Merge*, i.e. movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MergeKind {
+    Internal,
+    External,
+}
+
+
+
+impl MergeKind {
+    /// Classify Merge(A, B), where A is a root in the workspace.
+    pub fn of<S: SO>(a: &S, b: &S) -> Self {
+        if a.contains(b) {
+            MergeKind::Internal
+        }
+        else {
+            MergeKind::External
+        }
+    }
+}
+
+
+
+impl fmt::Display for MergeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeKind::Internal => write!(f, "internal"),
+            MergeKind::External => write!(f, "external"),
+        }
+    }
+}
+
+
+
 /// Select.
 /// 
 /// Return a stage on success, and an error message on failure.
@@ -14,7 +85,7 @@ use crate::prelude::*;
 /// >Let $S$ be a stage in a derivation $S = \\langle \\textit{LA}, W \\rangle$.
 /// >
 /// >If lexical token $A \\in \\textit{LA}$, then $\\textrm{Select} (A, S) = \\langle \\textit{LA} - \\{ A \\}, W \\cup \\{ A \\} \\rangle$.
-pub fn select(a: LexicalItemToken, s: Stage) -> Result<Stage, String> {
+pub fn select<S: SO>(a: LexicalItemToken, s: Stage<S>) -> Result<Stage<S>, String> {
     let Stage { mut la, mut w } = s;
 
     if !la.remove(&a) {
@@ -23,7 +94,7 @@ pub fn select(a: LexicalItemToken, s: Stage) -> Result<Stage, String> {
         );
     }
 
-    w.insert(SyntacticObject::LexicalItemToken(a));
+    w.insert(S::from_lit(a));
 
     Ok(Stage { la, w })
 }
@@ -35,16 +106,14 @@ pub fn select(a: LexicalItemToken, s: Stage) -> Result<Stage, String> {
 /// From Definition 13 in C&S 2016, p. 47:
 /// 
 /// >Given any two distinct syntactic objects $A, B$, $\\textrm{Merge} (A, B) = \\{ A, B \\}$.
-pub fn token_based_merge(a: SyntacticObject, b: SyntacticObject, _w: &Workspace) -> Result<SyntacticObject, String> {
+pub fn token_based_merge<S: SO>(a: S, b: S, _w: &Workspace<S>) -> Result<S, CandsError<S>> {
     //  a and b must be distinct!
     if a == b {
-        return Err(
-            format!("TokenBasedMerge: error.\nThe provided syntactic objects are not distinct.\nSyntactic object 1:\n{:#?}\nSyntactic object 2:\n{:#?}", a, b)
-        );
+        return Err(CandsError::IdenticalOperands(a));
     }
 
     let pair = set!( a, b );
-    Ok(SyntacticObject::Set(pair))
+    Ok(S::from_set(pair))
 }
 
 
@@ -54,70 +123,135 @@ pub fn token_based_merge(a: SyntacticObject, b: SyntacticObject, _w: &Workspace)
 /// From Definition 27 in C&S 2016, p. 64:
 /// 
 /// >Given any two distinct syntactic objects $A, B$, where $\\textrm{Triggers} (A) \neq \\varnothing$ and $\\textrm{Triggers} (B) = \\varnothing$, $\\textrm{Merge} (A, B) = \\{ A, B \\}$.
-pub fn triggered_merge<T: Triggers>(a: SyntacticObject, b: SyntacticObject, w: &Workspace) -> Result<SyntacticObject, String> {
-    // eprintln!("Triggered Merge: A =\n{}", a);
-    // eprintln!("Triggered Merge: B =\n{}", b);
+///
+/// The trigger features are those of `ug` (see [`UniversalGrammar::triggers`]).
+pub fn triggered_merge<T: Triggers, S: SO>(ug: &UniversalGrammar<T>, a: S, b: S, w: &Workspace<S>) -> Result<S, CandsError<S>> {
+    LabeledWorkspace::<T, S>::with_ug(ug, w).triggered_merge(a, b)
+}
 
-    //  a and b must be distinct!
-    if a == b {
-        // eprintln!("Triggered Merge: Error. A == B.");
-        return Err(
-            format!("TriggeredMerge: error.\nThe provided syntactic objects are not distinct.\nSyntactic object 1:\n{:#?}\nSyntactic object 2:\n{:#?}", a, b)
-        );
+
+
+/// Return whether `so` is a strong phase, i.e. a maximal projection of one of the phase heads of `ug` (see [`UniversalGrammar::phase_heads`]).
+pub fn is_strong_phase<T: Triggers, S: SO>(ug: &UniversalGrammar<T>, so: &S, w: &Workspace<S>) -> bool {
+    LabeledWorkspace::<T, S>::with_ug(ug, w).is_strong_phase(so)
+}
+
+
+
+/// Transfer `so` in the phase `phase`, i.e. compute its PF and LF, with the trigger features of `ug`.
+pub fn transfer<T: Triggers, S: SO>(ug: &UniversalGrammar<T>, phase: &S, so: S, w: &Workspace<S>) -> Result<S, CandsError<S>> {
+    LabeledWorkspace::<T, S>::with_ug(ug, w).transfer(phase, so)
+}
+
+
+
+/// Transfer the complement of the head of the phase `so`, with the trigger features of `ug`.
+pub fn cyclic_transfer<T: Triggers, S: SO>(ug: &UniversalGrammar<T>, so: S, w: &Workspace<S>) -> Result<S, CandsError<S>> {
+    LabeledWorkspace::<T, S>::with_ug(ug, w).cyclic_transfer(so)
+}
+
+
+
+impl<'a, T: Triggers, S: SO> LabeledWorkspace<'a, T, S> {
+    /// Triggered Merge, where the trigger features of A and B are looked up in `self`. See [`triggered_merge`].
+    pub fn triggered_merge(&self, a: S, b: S) -> Result<S, CandsError<S>> {
+        // eprintln!("Triggered Merge: A =\n{}", a);
+        // eprintln!("Triggered Merge: B =\n{}", b);
+
+        //  a and b must be distinct!
+        if a == b {
+            // eprintln!("Triggered Merge: Error. A == B.");
+            return Err(CandsError::IdenticalOperands(a));
+        }
+
+        //  a must have at least one trigger feature!
+        let tfs_a = self.triggers(&a)?;
+        if tfs_a.is_empty() {
+            // eprintln!("Triggered Merge: Error. Triggers(A) = {:?}", tfs_a);
+            return Err(CandsError::NoTriggers(a));
+        }
+        // eprintln!("Triggered Merge: So far so good. Triggers(A) = {:?}", tfs_a);
+
+        //  b must have zero trigger features!
+        let tfs_b = self.triggers(&b)?;
+        if !tfs_b.is_empty() {
+            // eprintln!("Triggered Merge: Error. Triggers(B) = {:?}", tfs_b);
+            return Err(CandsError::UncheckedTriggers(b));
+        }
+        // eprintln!("Triggered Merge: So far so good. Triggers(B) = {:?}", tfs_b);
+
+        let pair = set!( a, b );
+        Ok(S::from_set(pair))
     }
 
-    //  a must have at least one trigger feature!
-    let tfs_a = T::triggers(&a, w).map_err(|_| format!("tf a fail"))?;
-    if tfs_a.is_empty() {
-        // eprintln!("Triggered Merge: Error. Triggers(A) = {:?}", tfs_a);
-        return Err(
-            format!("TriggeredMerge: error.\nfor Merge (A, B), A must have at least one trigger feature.")
-        );
+    /// See [`is_strong_phase`].
+    pub fn is_strong_phase(&self, so: &S) -> bool {
+        self.workspace().contained_sos(false)
+            .find(|&maybe_head| {
+                match maybe_head.view() {
+                    SOView::LexicalItemToken(maybe_label) => {
+                        !maybe_label.li.syn.is_disjoint(self.phase_heads()) &&
+                        self.is_maximal_projection_of(so, maybe_label)
+                    },
+                    _ => false,
+                }
+            })
+            .is_some()
     }
-    // eprintln!("Triggered Merge: So far so good. Triggers(A) = {:?}", tfs_a);
 
-    //  b must have zero trigger features!
-    let tfs_b = T::triggers(&b, w).map_err(|_| format!("tf b fail"))?;
-    if !tfs_b.is_empty() {
-        // eprintln!("Triggered Merge: Error. Triggers(B) = {:?}", tfs_b);
-        return Err(
-            format!("TriggeredMerge: error.\nfor Merge (A, B), B must have zero trigger features, but tfs of B = {:?}.", tfs_b)
-        );
+    /// Return a strong phase contained in `so`, together with the complement of its head, if that complement has not been transferred yet.
+    ///
+    /// By Derive-by-Transfer, `so` cannot be transferred if there is such a strong phase (see [`TransferPolicy::Strict`](crate::deriv::TransferPolicy::Strict)).
+    pub fn untransferred_phase_in<'b>(&self, so: &'b S) -> Option<(&'b S, &'b S)> {
+        so.contained_sos(false, true)
+            //  Look for a strong phase SO2...
+            .filter(|&so2| self.is_strong_phase(so2))
+            //  with a head...
+            .filter_map(|so2| Some((so2, S::from_lit(self.label_of(so2).ok()?))))
+            //  whose complement SO4 in some SO3 contained in SO2 has not been transferred yet.
+            .find_map(|(so2, head)| {
+                so2.contained_sos(true, true)
+                    .find_map(|so3| {
+                        match so3.view() {
+                            SOView::Set(set) => set.iter()
+                                .find(|&so4| self.is_complement_of(so4, &head, so3) && !so4.is_transfer()),
+                            _ => None,
+                        }
+                    })
+                    .map(|so4| (so2, so4))
+            })
     }
-    // eprintln!("Triggered Merge: So far so good. Triggers(B) = {:?}", tfs_b);
 
-    let pair = set!( a, b );
-    Ok(SyntacticObject::Set(pair))
-}
+    /// See [`transfer`].
+    pub fn transfer(&self, phase: &S, so: S) -> Result<S, CandsError<S>> {
+        // eprintln!("Transfer: We are trying to transfer SO =\n{}", so);
+        // eprintln!("Transfer: In the phase\n{}", phase);
+        let pf = transfer_pf(phase, &so, self)?;
+        let lf = transfer_lf(phase, &so)?;
 
+        Ok(S::from_transfer(so, pf, lf))
+    }
 
+    /// See [`cyclic_transfer`].
+    pub fn cyclic_transfer(&self, so: S) -> Result<S, CandsError<S>> {
+        let head = S::from_lit(self.label_of(&so)?);
 
-pub fn is_strong_phase<T: Triggers>(so: &SyntacticObject, w: &Workspace) -> bool {
-    w.contained_sos(false)
-        .find(|&maybe_head| {
-            match maybe_head {
-                &SyntacticObject::LexicalItemToken(ref maybe_label) => {
-                    so.is_maximal_projection_of::<T>(maybe_label, w) &&
-                    (
-                        maybe_label.li.syn.contains(&comp_feature!()) ||
-                        maybe_label.li.syn.contains(&strong_light_verb_feature!())
-                    )
-                },
-                _ => false,
-            }
-        })
-        .is_some()
+        unwind_and_transfer(&so, &so, &head, self)?
+            .ok_or(CandsError::NoComplement(so))
+    }
 }
 
 
 
-fn transfer_pf<T: Triggers>(phase: &SyntacticObject, so: &SyntacticObject, w: &Workspace) -> Vec<Feature> {
-    let res = match so {
-        &SyntacticObject::LexicalItemToken(ref lit) =>
+fn transfer_pf<T: Triggers, S: SO>(phase: &S, so: &S, lw: &LabeledWorkspace<T, S>) -> Result<Vec<Feature>, CandsError<S>> {
+    let res = match so.view() {
+        SOView::LexicalItemToken(lit) =>
             lit.li.phon.iter().map(|f| f.clone()).collect::<Vec<_>>(),
 
-        &SyntacticObject::Set(ref set) => {
-            assert!(set.len() == 2); // todo: don't assert, return Result
+        SOView::Set(set) => {
+            if set.len() != 2 {
+                return Err(CandsError::NotBinary(so.clone()));
+            }
 
             let mut it = set.iter();
 
@@ -126,7 +260,7 @@ fn transfer_pf<T: Triggers>(phase: &SyntacticObject, so: &SyntacticObject, w: &W
 
             let mut pf1 = 
                 if x1.is_final(so, phase) {
-                    Some(transfer_pf::<T>(phase, x1, w))
+                    Some(transfer_pf(phase, x1, lw)?)
                 }
                 else {
                     None
@@ -134,7 +268,7 @@ fn transfer_pf<T: Triggers>(phase: &SyntacticObject, so: &SyntacticObject, w: &W
 
             let mut pf2 =
                 if x2.is_final(so, phase) {
-                    Some(transfer_pf::<T>(phase, x2, w))
+                    Some(transfer_pf(phase, x2, lw)?)
                 }
                 else {
                     None
@@ -149,18 +283,18 @@ fn transfer_pf<T: Triggers>(phase: &SyntacticObject, so: &SyntacticObject, w: &W
 
             match (pf1, pf2) {
                 (Some(mut pf1), Some(mut pf2)) => {
-                    if (x2.is_complement_of::<T>(x1, so, w) ||
-                        x1.is_specifier_of::<T>(x2, so, w)) {
+                    if (lw.is_complement_of(x2, x1, so) ||
+                        lw.is_specifier_of(x1, x2, so)) {
                         pf1.extend(pf2);
                         pf1
                     }
-                    else if (x1.is_complement_of::<T>(x2, so, w) ||
-                        x2.is_specifier_of::<T>(x1, so, w)) {
+                    else if (lw.is_complement_of(x1, x2, so) ||
+                        lw.is_specifier_of(x2, x1, so)) {
                         pf2.extend(pf1);
                         pf2
                     }
                     else {
-                        panic!()
+                        return Err(CandsError::Unordered(so.clone()));
                     }
                 },
                 (Some(pf1), None) => pf1,
@@ -169,145 +303,85 @@ fn transfer_pf<T: Triggers>(phase: &SyntacticObject, so: &SyntacticObject, w: &W
             }
         },
 
-        &SyntacticObject::Transfer { ref pf, .. } => pf.clone(),
+        SOView::Transfer { pf, .. } => pf.to_vec(),
     };
 
     // eprintln!("TransferPF: Result = {:?}", res);
-    res
+    Ok(res)
 }
 
 
 
-fn transfer_lf(phase: &SyntacticObject, so: &SyntacticObject) -> Set<Feature> {
-    match so {
-        &SyntacticObject::LexicalItemToken(ref lit) =>
-            lit.li.sem.clone(),
+fn transfer_lf<S: SO>(phase: &S, so: &S) -> Result<Set<Feature>, CandsError<S>> {
+    match so.view() {
+        SOView::LexicalItemToken(lit) =>
+            Ok(lit.li.sem.clone()),
 
-        &SyntacticObject::Set(ref vec) => {
-            assert!(vec.len() == 2); // todo: don't assert, return Result
+        SOView::Set(vec) => {
+            if vec.len() != 2 {
+                return Err(CandsError::NotBinary(so.clone()));
+            }
             vec.iter()
                 .map(|so| transfer_lf(phase, so))
-                .fold(
+                .try_fold(
                     set!(),
-                    |mut acc, sem| { acc.extend(sem.into_iter()); acc }
+                    |mut acc, sem| { acc.extend(sem?.into_iter()); Ok(acc) }
                 )
         },
 
-        &SyntacticObject::Transfer { ref lf, .. } => lf.clone()
+        SOView::Transfer { lf, .. } => Ok(lf.clone())
     }
 }
 
 
 
-pub fn transfer<T: Triggers>(phase: &SyntacticObject, so: SyntacticObject, w: &Workspace) -> SyntacticObject {
-    // eprintln!("Transfer: We are trying to transfer SO =\n{}", so);
-    // eprintln!("Transfer: In the phase\n{}", phase);
-    let pf = transfer_pf::<T>(&phase, &so, w);
-    let lf = transfer_lf(&phase, &so);
-
-    SyntacticObject::Transfer { so: Box::new(so), pf, lf }
-}
-
-
-
-fn unwind_and_transfer<T: Triggers>(phase: &SyntacticObject, mut so: SyntacticObject, head: &SyntacticObject, w: &Workspace) -> Result<SyntacticObject, SyntacticObject> {
-    enum Action {
-        Unwind, TransferFirst, TransferSecond, Return,
-    }
-
-    //  First, figure out what we should do
-    let action = match so {
-        SyntacticObject::Set(ref set) => {
+/// Transfer the complement of `head` in `so`, or in every syntactic object contained in `so` where there is one.
+///
+/// Return the resulting syntactic object, or `None` if nothing has been transferred.
+fn unwind_and_transfer<T: Triggers, S: SO>(phase: &S, so: &S, head: &S, lw: &LabeledWorkspace<T, S>) -> Result<Option<S>, CandsError<S>> {
+    match so.view() {
+        SOView::Set(set) => {
             if set.len() == 2 {
                 let mut it = set.iter();
-                
+
                 let x0 = it.next().unwrap();
                 let x1 = it.next().unwrap();
 
-                if x0.is_complement_of::<T>(&head, &so, w) {
+                if lw.is_complement_of(x0, head, so) {
                     // eprintln!("UnwindAndTransfer: This phase:\n{}", so);
                     // eprintln!("UnwindAndTransfer: Has the head:\n{}", head);
-                    // eprintln!("UnwindAndTransfer: And the complement:\n{}", vec[0]);
+                    // eprintln!("UnwindAndTransfer: And the complement:\n{}", x0);
                     // eprintln!("UnwindAndTransfer: Therefore, the complement will be transferred.");
-                    Action::TransferFirst
+                    return Ok(Some(S::from_set(set!( lw.transfer(phase, x0.clone())?, x1.clone() ))));
                 }
-                else if x1.is_complement_of::<T>(&head, &so, w) {
+                else if lw.is_complement_of(x1, head, so) {
                     // eprintln!("UnwindAndTransfer: This phase:\n{}", so);
                     // eprintln!("UnwindAndTransfer: Has the head:\n{}", head);
-                    // eprintln!("UnwindAndTransfer: And the complement:\n{}", vec[1]);
+                    // eprintln!("UnwindAndTransfer: And the complement:\n{}", x1);
                     // eprintln!("UnwindAndTransfer: Therefore, the complement will be transferred.");
-                    Action::TransferSecond
-                }
-                else {
-                    Action::Unwind
+                    return Ok(Some(S::from_set(set!( x0.clone(), lw.transfer(phase, x1.clone())? ))));
                 }
             }
-            else {
-                Action::Unwind
-            }
-        },
-        _ => Action::Return,
-    };
-
-    //  Then carry out the action
-    match action {
-        Action::Return => Err(so),
-        Action::Unwind => {
-            if let SyntacticObject::Set(vec) = so {
-                let (set, is_ok) = vec.into_iter()
-                    .map(|so| {
-                        match unwind_and_transfer::<T>(phase, so, head, w) {
-                            Ok(so) => (so, true),
-                            Err(so) => (so, false),
-                        }
-                    })
-                    .fold(
-                        (set!(), false),
-                        |(mut set, is_ok1), (so, is_ok2)| {
-                            set.insert(so);
-                            (set, is_ok1 || is_ok2)
-                        }
-                    );
 
-                match is_ok {
-                    true => Ok(SyntacticObject::Set(set)),
-                    false => Err(SyntacticObject::Set(set)),
+            //  Unwind.
+            let mut is_ok = false;
+            let mut unwound = set!();
+
+            for child in set {
+                match unwind_and_transfer(phase, child, head, lw)? {
+                    Some(child) => {
+                        unwound.insert(child);
+                        is_ok = true;
+                    },
+                    None => {
+                        unwound.insert(child.clone());
+                    },
                 }
             }
-            else {
-                panic!()
-            }
-        },
 
-        _ => {
-            if let SyntacticObject::Set(ref set) = so {
-                let mut it = set.iter();
-                let x1 = it.next().unwrap().clone();
-                let x2 = it.next().unwrap().clone();
-    
-                Ok(SyntacticObject::Set(match action {
-                    Action::TransferFirst => set!( transfer::<T>(phase, x1, w), x2 ),
-                    Action::TransferSecond => set!( x1, transfer::<T>(phase, x2, w) ),
-                    _ => panic!(),
-                }))
-            }
-            else {
-                panic!()
-            }
+            Ok(if is_ok { Some(S::from_set(unwound)) } else { None })
         },
+        _ => Ok(None),
     }
 }
 
-
-
-pub fn cyclic_transfer<T: Triggers>(so: SyntacticObject, w: &Workspace) -> Result<SyntacticObject, ()> {
-    match T::label_of(&so, w) {
-        Ok(label) => {
-            let head = so!(label.clone());
-            unwind_and_transfer::<T>(&so, so.clone(), &head, w).map_err(|_| ())
-        },
-        _ => {
-            Err(())
-        }
-    }
-}
\ No newline at end of file
@@ -11,24 +11,34 @@
 //  Why pub(crate) not pub?
 //  https://stackoverflow.com/a/41667202
 
+pub(crate) use crate::error::{ CandsError };
 pub(crate) use crate::utils::{
     Set, set,
-    inc, dec, my_trace, my_debug, my_info, my_error,
+    inc, dec, my_trace, my_debug, my_info, my_warn, my_error,
 };
 pub(crate) use crate::feature::{
-    Feature, f, fset, fvec,
+    Feature, f,
     wh_feature,
     epp_feature,
-    comp_feature,
-    strong_light_verb_feature,
-    CATSEL_FEATURE_PREFIX
+    CATSEL_FEATURE_PREFIX,
+    default_phase_heads,
 };
-pub(crate) use crate::ops::{ select, token_based_merge, triggered_merge, is_strong_phase, transfer, cyclic_transfer };
+pub(crate) use crate::ops::{ Operation, MergeKind, select };
 pub(crate) use crate::deriv::{
-    Lexicon, UniversalGrammar, ILanguage, Stage, is_derivation, Workspace, w,
-    li::{ LexicalItem, li },
-    lit::{ LexicalItemToken, lit },
-    so::{ SyntacticObject, ContainedSyntacticObjects, SOPrefixFormatter, so },
+    UniversalGrammar, PicPolicy, ILanguage, Stage, OperationError, Workspace, w,
+    li::{ LexicalItem },
+    lit::{ LexicalItemToken },
+    so::{ SO, SOView, SyntacticObject, ContainedSyntacticObjects, SOPrefixFormatter, so },
+    occ::{ Path, Occurrence },
 };
-pub(crate) use crate::labels::{ Triggers, BasicTriggers };
+pub(crate) use crate::labels::{ Triggers, TriggersWarning, labeled::{ LabeledWorkspace } };
+
+//  Only the tests use these.
+#[cfg(test)]
+pub(crate) use crate::feature::{ fset, fvec };
+#[cfg(test)]
+pub(crate) use crate::deriv::{ is_derivation, li::{ li }, lit::{ lit } };
+#[cfg(test)]
+pub(crate) use crate::labels::{ BasicTriggers };
+#[cfg(test)]
 pub(crate) use crate::conv::{ converges_ci, converges_sm, converges };
\ No newline at end of file
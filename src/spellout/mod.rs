@@ -0,0 +1,225 @@
+use crate::prelude::*;
+
+use std::collections::{ HashMap };
+use std::fmt;
+
+
+
+/// Which copy of an internally merged syntactic object to pronounce.
+///
+/// Copies are ordered from the highest to the lowest, i.e. by the length of their positions (see [`Path`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyPolicy {
+    /// Pronounce the highest copy.
+    Highest,
+    /// Pronounce the lowest copy.
+    Lowest,
+    /// Pronounce the `n`-th copy, counting from the highest copy, which is the 0-th copy. If there are not enough copies, pronounce the lowest copy.
+    Nth(usize),
+}
+
+
+
+impl CopyPolicy {
+    /// Choose one of the occurrences `occs` of the same syntactic object.
+    fn choose<'a, 'b>(&self, mut occs: Vec<&'b Path<'a>>) -> Option<&'b Path<'a>> {
+        occs.sort_by_key(|path| (path.len(), *path));
+
+        match self {
+            CopyPolicy::Highest => occs.first().copied(),
+            CopyPolicy::Lowest => occs.last().copied(),
+            CopyPolicy::Nth(n) => occs.get(*n).or_else(|| occs.last()).copied(),
+        }
+    }
+}
+
+
+
+/// The reason why a stage cannot be spelled out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpellOutError {
+    /// The workspace does not consist of exactly one syntactic object.
+    NotSingleRoot,
+    /// Neither daughter of this syntactic object is the head, the complement or the specifier of the other, so they cannot be ordered.
+    Unordered(SyntacticObject),
+}
+
+
+
+impl fmt::Display for SpellOutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpellOutError::NotSingleRoot =>
+                write!(f, "The workspace must consist of exactly one syntactic object."),
+            SpellOutError::Unordered(so) =>
+                write!(f, "Can't linearize this syntactic object: {}", SOPrefixFormatter::new(so, 39)),
+        }
+    }
+}
+
+
+
+/// Spell-Out.
+///
+/// Return the phonological features of the syntactic object in the workspace of `stage`, e.g. the final stage of a converged derivation, in linear order.
+///
+/// The heads, complements and specifiers are determined with the trigger features of `ug`. Heads precede their complements and specifiers precede their heads, as in Transfer. Of the copies created by internal Merge, only the one chosen by `policy` is pronounced; the others are deleted along with everything they contain. Unlike the PF computed by Transfer, which always keeps the highest copy within each phase, this applies `policy` across phases, so the syntactic objects in already transferred phases are spelled out again.
+pub fn spell_out<T: Triggers>(ug: &UniversalGrammar<T>, stage: &Stage, policy: CopyPolicy) -> Result<Vec<Feature>, SpellOutError> {
+    let w = &stage.w;
+
+    let root = match w.iter().next() {
+        Some(root) if w.len() == 1 => root,
+        _ => return Err(SpellOutError::NotSingleRoot),
+    };
+
+    let lw = LabeledWorkspace::with_ug(ug, w);
+    let mut spell_out = SpellOut { root, lw, policy, deleted: HashMap::new() };
+    spell_out.linearize(Path::new(vec![ root ]))
+}
+
+
+
+/// The state of Spell-Out of one syntactic object.
+struct SpellOut<'a, T: Triggers> {
+    root: &'a SyntacticObject,
+    lw: LabeledWorkspace<'a, T>,
+    policy: CopyPolicy,
+    /// For each position, whether the copy at that position is deleted for its own sake, i.e. not because a syntactic object containing it is deleted.
+    deleted: HashMap<Path<'a>, bool>,
+}
+
+
+
+impl<'a, T: Triggers> SpellOut<'a, T> {
+    /// Check if the copy at `path` is deleted for its own sake.
+    ///
+    /// Only the copies of a syntactic object that are not contained in a deleted copy of a bigger syntactic object compete for pronunciation. Since those bigger syntactic objects are decided first, this terminates.
+    fn is_deleted(&mut self, path: &Path<'a>) -> bool {
+        if path.len() == 1 {
+            return false;
+        }
+
+        if let Some(&deleted) = self.deleted.get(path) {
+            return deleted;
+        }
+
+        let occs = self.root.paths_to(path.end());
+
+        let mut alive = vec![];
+        for occ in occs.iter() {
+            if !(2..occ.len()).any(|k| self.is_deleted(&Path::new(occ[..k].to_vec()))) {
+                alive.push(occ);
+            }
+        }
+
+        let deleted = self.policy.choose(alive) != Some(path);
+        self.deleted.insert(path.clone(), deleted);
+        deleted
+    }
+
+    /// Return the phonological features of the copy at `path`, in linear order.
+    fn linearize(&mut self, path: Path<'a>) -> Result<Vec<Feature>, SpellOutError> {
+        if self.is_deleted(&path) {
+            return Ok(vec![]);
+        }
+
+        match path.end() {
+            SyntacticObject::LexicalItemToken(lit) =>
+                Ok(lit.li.phon.clone()),
+
+            SyntacticObject::Set(set) => {
+                let so = path.end();
+
+                let mut it = set.iter();
+                let (x1, x2) = match (it.next(), it.next(), it.next()) {
+                    (Some(x1), Some(x2), None) => (x1, x2),
+                    _ => return Err(SpellOutError::Unordered(so.clone())),
+                };
+
+                let (fst, snd) =
+                    if self.lw.is_complement_of(x2, x1, so) || self.lw.is_specifier_of(x1, x2, so) {
+                        (x1, x2)
+                    }
+                    else if self.lw.is_complement_of(x1, x2, so) || self.lw.is_specifier_of(x2, x1, so) {
+                        (x2, x1)
+                    }
+                    else {
+                        return Err(SpellOutError::Unordered(so.clone()));
+                    };
+
+                let mut pf = self.linearize(path.extended_by(fst))?;
+                pf.extend(self.linearize(path.extended_by(snd))?);
+                Ok(pf)
+            },
+
+            SyntacticObject::Transfer { so, .. } =>
+                self.linearize(path.extended_by(so)),
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    mod spellout {
+        use crate::prelude::*;
+        use crate::spellout::{ CopyPolicy, SpellOutError, spell_out };
+        use crate::tests::fixtures::{ init, mary_dances, john_was_helped };
+
+
+
+        #[test]
+        fn spell_out1() {
+            init();
+
+            let (il, stages) = mary_dances();
+            let stage = stages.last().unwrap();
+
+            assert_eq!(spell_out(&il.ug, stage, CopyPolicy::Highest), Ok(fvec!("Mary", "dances")));
+
+            //  The same as the PF computed by Transfer.
+            if let Some(SyntacticObject::Transfer { pf, .. }) = stage.w.iter().next() {
+                assert_eq!(spell_out(&il.ug, stage, CopyPolicy::Highest).as_ref(), Ok(pf));
+            }
+
+            assert_eq!(spell_out(&il.ug, &stages[9], CopyPolicy::Highest), Err(SpellOutError::NotSingleRoot));
+        }
+
+
+
+        #[test]
+        fn spell_out_copies() {
+            init();
+
+            let (ug, stages) = john_was_helped("=");
+            let stage = stages.last().unwrap();
+
+            assert_eq!(spell_out(&ug, stage, CopyPolicy::Highest), Ok(fvec!("John", "was", "helped")));
+            assert_eq!(spell_out(&ug, stage, CopyPolicy::Lowest), Ok(fvec!("was", "helped", "John")));
+            assert_eq!(spell_out(&ug, stage, CopyPolicy::Nth(0)), Ok(fvec!("John", "was", "helped")));
+            assert_eq!(spell_out(&ug, stage, CopyPolicy::Nth(5)), Ok(fvec!("was", "helped", "John")));
+        }
+
+
+
+        #[test]
+        fn spell_out_triggers() {
+            init();
+
+            //  "John was helped" with "+" as the selector prefix.
+            let (ug, stages) = john_was_helped("+");
+            let stage = stages.last().unwrap();
+
+            assert_eq!(spell_out(&ug, stage, CopyPolicy::Highest), Ok(fvec!("John", "was", "helped")));
+            assert_eq!(spell_out(&ug, stage, CopyPolicy::Lowest), Ok(fvec!("was", "helped", "John")));
+
+            //  With the default "=", nothing selects anything.
+            let root = stage.w.iter().next().unwrap().clone();
+            assert_eq!(
+                spell_out(&UniversalGrammar::<BasicTriggers>::new(set!(), set!(), set!()), stage, CopyPolicy::Highest),
+                Err(SpellOutError::Unordered(root))
+            );
+        }
+    }
+}
@@ -14,19 +14,57 @@ mod basic_select_tests {
 }
 
 #[cfg(test)]
-mod derivck_tests {
-    use crate::deriv::LexicalArray;
+pub(crate) mod fixtures {
+    //! Derivations shared by the tests.
+
+    use crate::deriv::{ LexicalArray, replay::{ replay } };
     use crate::{f, fset, fvec, set};
     use crate::prelude::*;
 
-    fn init() {
-        let _ = env_logger::builder().is_test(true).try_init();
+    use std::cell::{ RefCell };
+
+    thread_local! {
+        /// The warnings logged on the current thread, since the last call to [`take_warnings`].
+        static WARNINGS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
     }
 
-    #[test]
-    fn test1() {
-        init();
+    /// A logger that passes the records on to `env_logger`, and keeps the warnings for [`take_warnings`].
+    struct TestLogger(env_logger::Logger);
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            (metadata.level() == log::Level::Warn) || self.0.enabled(metadata)
+        }
+
+        fn log(&self, record: &log::Record) {
+            if record.level() == log::Level::Warn {
+                WARNINGS.with(|warnings| warnings.borrow_mut().push(record.args().to_string()));
+            }
+            if self.0.matches(record) {
+                self.0.log(record);
+            }
+        }
 
+        fn flush(&self) {
+            self.0.flush();
+        }
+    }
+
+    pub(crate) fn init() {
+        let logger = env_logger::builder().is_test(true).build();
+        let max_level = logger.filter().max(log::LevelFilter::Warn);
+        if log::set_boxed_logger(Box::new(TestLogger(logger))).is_ok() {
+            log::set_max_level(max_level);
+        }
+    }
+
+    /// Return the warnings logged on the current thread since the last call, and forget them.
+    pub(crate) fn take_warnings() -> Vec<String> {
+        WARNINGS.with(|warnings| warnings.take())
+    }
+
+    /// The I-language and derivation for "Mary dances".
+    pub(crate) fn mary_dances() -> (ILanguage<BasicTriggers>, Vec<Stage>) {
         let lex = set!(
             li!("Mary"; "D"; "Mary"),
             li!("dances"; "V"; "dances"),
@@ -126,6 +164,26 @@ mod derivck_tests {
                 ))
             },
             
+            Stage {
+                la: LexicalArray::new(set!(
+                    lit!(li!("PRES"; "T", "=v*";), 4),
+                    lit!(li!("C"; "C", "=T";), 5)
+                )),
+                w: Workspace::new(set!(
+                    so!(
+                        so!(
+                            so!(
+                                so!(lit!(li!("dances"; "V"; "dances"), 2)) =>
+                                fvec!( "dances" ) ;
+                                fset!( "dances" )
+                            ),
+                            so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
+                        ),
+                        so!(lit!(li!("Mary"; "D"; "Mary"), 1)),
+                    )
+                ))
+            },
+            
             Stage {
                 la: LexicalArray::new(set!(
                     lit!(li!("C"; "C", "=T";), 5)
@@ -134,7 +192,11 @@ mod derivck_tests {
                     so!(lit!(li!("PRES"; "T", "=v*";), 4)),
                     so!(
                         so!(
-                            so!(lit!(li!("dances"; "V"; "dances"), 2)),
+                            so!(
+                                so!(lit!(li!("dances"; "V"; "dances"), 2)) =>
+                                fvec!( "dances" ) ;
+                                fset!( "dances" )
+                            ),
                             so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
                         ),
                         so!(lit!(li!("Mary"; "D"; "Mary"), 1)),
@@ -151,7 +213,11 @@ mod derivck_tests {
                         so!(lit!(li!("PRES"; "T", "=v*";), 4)),
                         so!(
                             so!(
-                                so!(lit!(li!("dances"; "V"; "dances"), 2)),
+                                so!(
+                                    so!(lit!(li!("dances"; "V"; "dances"), 2)) =>
+                                    fvec!( "dances" ) ;
+                                    fset!( "dances" )
+                                ),
                                 so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
                             ),
                             so!(lit!(li!("Mary"; "D"; "Mary"), 1)),
@@ -168,7 +234,11 @@ mod derivck_tests {
                         so!(lit!(li!("PRES"; "T", "=v*";), 4)),
                         so!(
                             so!(
-                                so!(lit!(li!("dances"; "V"; "dances"), 2)),
+                                so!(
+                                    so!(lit!(li!("dances"; "V"; "dances"), 2)) =>
+                                    fvec!( "dances" ) ;
+                                    fset!( "dances" )
+                                ),
                                 so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
                             ),
                             so!(lit!(li!("Mary"; "D"; "Mary"), 1)),
@@ -186,7 +256,11 @@ mod derivck_tests {
                             so!(lit!(li!("PRES"; "T", "=v*";), 4)),
                             so!(
                                 so!(
-                                    so!(lit!(li!("dances"; "V"; "dances"), 2)),
+                                    so!(
+                                        so!(lit!(li!("dances"; "V"; "dances"), 2)) =>
+                                        fvec!( "dances" ) ;
+                                        fset!( "dances" )
+                                    ),
                                     so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
                                 ),
                                 so!(lit!(li!("Mary"; "D"; "Mary"), 1)),
@@ -206,7 +280,11 @@ mod derivck_tests {
                                 so!(lit!(li!("PRES"; "T", "=v*";), 4)),
                                 so!(
                                     so!(
-                                        so!(lit!(li!("dances"; "V"; "dances"), 2)),
+                                        so!(
+                                            so!(lit!(li!("dances"; "V"; "dances"), 2)) =>
+                                            fvec!( "dances" ) ;
+                                            fset!( "dances" )
+                                        ),
                                         so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
                                     ),
                                     so!(lit!(li!("Mary"; "D"; "Mary"), 1)),
@@ -220,7 +298,689 @@ mod derivck_tests {
             },
         ];
 
+        (il, stages)
+    }
+
+    /// The I-language and derivation for "Mary helped John", where "John" moves out of the transferred VP to the specifier of C.
+    ///
+    /// Moving "John" violates the PIC, so the derivation is built without PIC, but the I-language has the default PIC1.
+    pub(crate) fn mary_helped_john() -> (ILanguage<BasicTriggers>, Vec<Stage>) {
+        let (mut il, _) = mary_dances();
+
+        //  A transitive verb, so that a set is transferred, and C with EPP, so that something can move to its specifier.
+        let mary = lit!(li!("Mary"; "D"; "Mary"), 1);
+        let helped = lit!(li!("help"; "V", "=D"; "helped"), 2);
+        let v = lit!(li!("v*"; "v*", "=V", "=D";), 3);
+        let pres = lit!(li!("PRES"; "T", "=v*";), 4);
+        let c = lit!(li!("C"; "C", "=T", "EPP";), 5);
+        let john = lit!(li!("John"; "D"; "John"), 6);
+        for lit in [ &helped, &c, &john ] {
+            il.lex.insert(lit.li.clone());
+        }
+        il.ug.phon_f.extend(fset!("helped", "John"));
+        il.ug.sem_f.extend(fset!("help", "John"));
+        il.ug.syn_f.insert(f!("EPP"));
+
+        let vp = so!(so!(helped.clone()), so!(john.clone()),);
+        let v1 = so!(so!(v.clone()), vp.clone(),);
+        let v_p = so!(v1.clone(), so!(mary.clone()),);
+        let la = LexicalArray::new(set!(mary.clone(), helped.clone(), v.clone(), pres.clone(), c.clone(), john.clone()));
+        let mut stages = replay(&il.ug, la, &[
+            Operation::Select(helped.clone()),
+            Operation::Select(john.clone()),
+            Operation::Merge(so!(helped), so!(john.clone())),
+            Operation::Select(v.clone()),
+            Operation::Merge(so!(v), vp),
+            Operation::Select(mary.clone()),
+            Operation::Merge(v1, so!(mary)),
+            Operation::CyclicTransfer(v_p),
+            Operation::Select(pres.clone()),
+        ]).unwrap();
+
+        //  The v*P, where the VP "helped John" has been transferred.
+        let v_p = stages[9].w.iter().find(|so| so.is_set()).unwrap().clone();
+        let tp = so!(so!(pres.clone()), v_p.clone(),);
+        let cp = so!(so!(c.clone()), tp.clone(),);
+        let mut no_pic = il.ug.clone();
+        no_pic.pic_policy = PicPolicy::None;
+        for op in [
+            Operation::Merge(so!(pres), v_p),
+            Operation::Select(c.clone()),
+            Operation::Merge(so!(c), tp),
+            Operation::Merge(cp, so!(john)),
+        ] {
+            let next = stages.last().unwrap().apply(&no_pic, &op).unwrap();
+            stages.push(next);
+        }
+
+        (il, stages)
+    }
+
+    /// The I-language and derivation for "Mary dances" with EPP on T, where "Mary" moves to the specifier of T.
+    pub(crate) fn mary_dances_epp() -> (ILanguage<BasicTriggers>, Vec<Stage>) {
+        let mary = lit!(li!("Mary"; "D"; "Mary"), 1);
+        let dances = lit!(li!("dances"; "V"; "dances"), 2);
+        let v = lit!(li!("v*"; "v*", "=V", "=D";), 3);
+        let pres = lit!(li!("PRES"; "T", "=v*", "EPP";), 4);
+
+        let il = ILanguage {
+            lex: set!(mary.li.clone(), dances.li.clone(), v.li.clone(), pres.li.clone()),
+            ug: UniversalGrammar::<BasicTriggers>::new(
+                fset!("Mary", "dances"),
+                fset!("D", "V", "v*", "T", "=D", "=V", "=v*", "EPP"),
+                fset!("Mary", "dances", "v*", "PRES")
+            ),
+        };
+
+        let v1 = so!(so!(v.clone()), so!(dances.clone()),);
+        let vp = so!(v1.clone(), so!(mary.clone()),);
+        let t1 = so!(so!(pres.clone()), vp.clone(),);
+
+        let stages = replay(&il.ug,
+            LexicalArray::new(set!(mary.clone(), dances.clone(), v.clone(), pres.clone())),
+            &[
+                Operation::Select(dances.clone()),
+                Operation::Select(v.clone()),
+                Operation::Merge(so!(v), so!(dances)),
+                Operation::Select(mary.clone()),
+                Operation::Merge(v1, so!(mary.clone())),
+                Operation::Select(pres.clone()),
+                Operation::Merge(so!(pres), vp),
+                Operation::Merge(t1, so!(mary)),
+            ]
+        ).unwrap();
+
+        (il, stages)
+    }
+
+    /// The UG and derivation for "John was helped", where "John" moves to the specifier of T, with `prefix` as the prefix of the category selection features.
+    pub(crate) fn john_was_helped(prefix: &str) -> (UniversalGrammar<BasicTriggers>, Vec<Stage>) {
+        let sel = |cat: &str| format!("{}{}", prefix, cat);
+
+        //  [ John [ was [ helped John ] ] ]
+        let john = lit!(li!("John"; "D"; "John"), 1);
+        let was = lit!(li!(; "T", sel("V"), "EPP"; "was"), 2);
+        let helped = lit!(li!("help"; "V", sel("D"); "helped"), 3);
+
+        let vp = so!(so!(helped.clone()), so!(john.clone()),);
+        let t1 = so!(so!(was.clone()), vp.clone(),);
+
+        let mut ug = UniversalGrammar::<BasicTriggers>::new(
+            fset!("John", "was", "helped"),
+            fset!("D", "T", "V", sel("V"), sel("D"), "EPP"),
+            fset!("John", "help")
+        );
+        ug.triggers = BasicTriggers::new(fset!("wh"), fset!("EPP"), prefix.to_string()).unwrap();
+
+        let stages = replay(&ug,
+            LexicalArray::new(set!(john.clone(), was.clone(), helped.clone())),
+            &[
+                Operation::Select(helped.clone()),
+                Operation::Select(john.clone()),
+                Operation::Merge(so!(helped), so!(john.clone())),
+                Operation::Select(was.clone()),
+                Operation::Merge(so!(was), vp),
+                Operation::Merge(t1, so!(john)),
+            ]
+        ).unwrap();
+
+        (ug, stages)
+    }
+}
+
+
+
+#[cfg(test)]
+mod derivck_tests {
+    use crate::deriv::{
+        LexicalArray, TransferPolicy, DerivationError, FeatureComponent,
+        check_derivation, check_derivation_parallel, is_derivation_parallel,
+        interned::{ InternedSO },
+        search::{ fill_gaps },
+        replay::{ replay },
+        tree::{ Tree },
+    };
+    use crate::ops::{ triggered_merge, is_strong_phase, transfer, cyclic_transfer };
+    use crate::labels::{ TriggersError };
+    use crate::conv::{ ConvergenceError };
+    use crate::{f, fset, fvec, set};
+    use crate::prelude::*;
+    use super::fixtures::{ init, take_warnings, mary_dances, mary_dances_epp };
+
+    #[test]
+    fn test1() {
+        init();
+
+        let (il, stages) = mary_dances();
+
+        assert!(is_derivation(&il, &stages));
+        assert_eq!(converges(&il.ug, &stages), Ok(()));
+    }
+
+    #[test]
+    fn convergence_errors() {
+        init();
+
+        let (il, stages) = mary_dances();
+
+        assert_eq!(converges(&il.ug, &[]), Err(ConvergenceError::NoStages));
+        assert_eq!(converges(&il.ug, &stages[..1]), Err(ConvergenceError::LexicalArrayNotExhausted));
+        assert_eq!(converges(&il.ug, &stages[..11]), Err(ConvergenceError::NotTransferred));
+
+        //  UG without "Mary" in SEM-F and PHON-F.
+        let mut ug = UniversalGrammar::<BasicTriggers>::new(il.ug.phon_f.clone(), il.ug.syn_f.clone(), il.ug.sem_f.clone());
+        ug.sem_f.remove(&f!("Mary"));
+        assert_eq!(converges_ci(&ug, &stages), Err(ConvergenceError::NotSemantic(f!("Mary"))));
+        assert_eq!(converges_sm(&ug, &stages), Ok(()));
+        ug.phon_f.remove(&f!("Mary"));
+        assert_eq!(converges_sm(&ug, &stages), Err(ConvergenceError::NotPhonological(f!("Mary"))));
+
+        //  Transfer v* before it selects anything.
+        let v = lit!(li!("v*"; "v*", "=V", "=D";), 3);
+        let deriv = replay(&il.ug, LexicalArray::new(set!(v.clone())), &[
+            Operation::Select(v.clone()),
+            Operation::Transfer(so!(v)),
+        ]).unwrap();
+        assert_eq!(
+            converges(&il.ug, &deriv),
+            Err(ConvergenceError::UncheckedTriggers(fset!("=V", "=D")))
+        );
+    }
+
+    #[test]
+    fn report1() {
+        init();
+
+        let (il, stages) = mary_dances();
+        let report = check_derivation(&il, &stages).unwrap();
+
+        assert_eq!(report.steps.len(), stages.len() - 1);
+        for (k, step) in report.steps.iter().enumerate() {
+            assert_eq!(step.stage, k + 1);
+        }
+
+        let ops: Vec<&str> = report.steps.iter()
+            .map(|step| match step.op {
+                Operation::Select(_) => "Select",
+                Operation::Merge(..) => "Merge",
+                Operation::Transfer(_) => "Transfer",
+                Operation::CyclicTransfer(_) => "Cyclic-Transfer",
+            })
+            .collect();
+        assert_eq!(ops, vec![
+            "Select", "Select", "Merge", "Select", "Merge", "Cyclic-Transfer",
+            "Select", "Merge", "Select", "Merge", "Transfer",
+        ]);
+
+        assert_eq!(
+            report.steps[0].op,
+            Operation::Select(lit!(li!("dances"; "V"; "dances"), 2))
+        );
+        assert_eq!(
+            report.steps[2].op,
+            Operation::Merge(
+                so!(lit!(li!("v*"; "v*", "=V", "=D";), 3)),
+                so!(lit!(li!("dances"; "V"; "dances"), 2))
+            )
+        );
+    }
+
+    #[test]
+    fn merge_from_new_root() {
+        init();
+
+        let (il, stages) = mary_dances();
+
+        let mary = lit!(li!("Mary"; "D"; "Mary"), 1);
+        let dances = lit!(li!("dances"; "V"; "dances"), 2);
+        let v = lit!(li!("v*"; "v*", "=V", "=D";), 3);
+        let mut stages = replay(&il.ug, stages[0].la.clone(), &[
+            Operation::Select(mary.clone()),
+            Operation::Select(dances.clone()),
+            Operation::Select(v.clone()),
+            Operation::Merge(so!(v.clone()), so!(dances.clone())),
+        ]).unwrap();
+
+        let report = check_derivation(&il, &stages).unwrap();
+        assert_eq!(
+            report.steps[3].op,
+            Operation::Merge(so!(v.clone()), so!(dances.clone()))
+        );
+
+        //  Merge(A, B) must be the only new root.
+        let last = stages.pop().unwrap();
+        let mut w = last.w;
+        w.0.remove(&so!(mary.clone()));
+        w.0.insert(so!(so!(mary.clone()),));
+        stages.push(Stage { la: last.la, w });
+        assert_eq!(
+            check_derivation(&il, &stages),
+            Err(DerivationError::NoMatchingRule { stage: 4, reason: None })
+        );
+    }
+
+    #[test]
+    fn parallel() {
+        init();
+
+        let (il, stages) = mary_dances();
+
+        for threads in [ 1, 2, 4, 16 ] {
+            assert_eq!(check_derivation_parallel(&il, &stages, threads), check_derivation(&il, &stages));
+            assert!(is_derivation_parallel(&il, &stages, threads));
+
+            //  Skip two Merges. The first failure is reported.
+            let mut bad = stages.clone();
+            bad.remove(8);
+            bad.remove(3);
+            assert_eq!(
+                check_derivation_parallel(&il, &bad, threads),
+                Err(DerivationError::NoMatchingRule { stage: 3, reason: None })
+            );
+            assert_eq!(check_derivation_parallel(&il, &bad, threads), check_derivation(&il, &bad));
+        }
+    }
+
+    #[test]
+    fn report_errors() {
+        init();
+
+        let (il, stages) = mary_dances();
+
+        assert_eq!(check_derivation::<_, SyntacticObject>(&il, &[]), Err(DerivationError::NoStages));
+
+        //  Skip the first Merge.
+        let mut bad = stages.clone();
+        bad.remove(3);
+        assert_eq!(
+            check_derivation(&il, &bad),
+            Err(DerivationError::NoMatchingRule { stage: 3, reason: None })
+        );
+
+        //  Merge "Mary" and "dances", neither of which has trigger features.
+        let mary = lit!(li!("Mary"; "D"; "Mary"), 1);
+        let dances = lit!(li!("dances"; "V"; "dances"), 2);
+        let la = LexicalArray::new(set!(mary.clone(), dances.clone()));
+        let mut bad = replay(&il.ug, la, &[
+            Operation::Select(mary.clone()),
+            Operation::Select(dances.clone()),
+        ]).unwrap();
+        bad.push(Stage {
+            la: LexicalArray::new(set!()),
+            w: w!(so!(so!(mary), so!(dances),)),
+        });
+        let err = check_derivation(&il, &bad).unwrap_err();
+        assert!(matches!(
+            err,
+            DerivationError::NoMatchingRule { stage: 3, reason: Some(CandsError::NoTriggers(_)) }
+        ));
+        assert!(err.to_string().contains("A must have trigger features"));
+
+        //  Start from the second stage.
+        assert_eq!(
+            check_derivation(&il, &stages[1..]),
+            Err(DerivationError::NonEmptyFirstWorkspace)
+        );
+
+        //  Use a lexical item that is not in the lexicon.
+        let mut bad = stages[..1].to_vec();
+        let lit = lit!(li!("Sue"; "D"; "Sue"), 6);
+        bad[0].la.insert(lit.clone());
+        assert_eq!(
+            check_derivation(&il, &bad),
+            Err(DerivationError::NotInLexicon(lit))
+        );
+    }
+
+    #[test]
+    fn transfer_policy() {
+        init();
+
+        let (mut il, stages) = mary_dances();
+
+        //  "Mary dances" without Cyclic-Transfer of the v*P.
+        let mary = lit!(li!("Mary"; "D"; "Mary"), 1);
+        let dances = lit!(li!("dances"; "V"; "dances"), 2);
+        let v = lit!(li!("v*"; "v*", "=V", "=D";), 3);
+        let pres = lit!(li!("PRES"; "T", "=v*";), 4);
+        let c = lit!(li!("C"; "C", "=T";), 5);
+
+        let v1 = so!(so!(v.clone()), so!(dances.clone()),);
+        let vp = so!(v1.clone(), so!(mary.clone()),);
+        let tp = so!(so!(pres.clone()), vp.clone(),);
+        let cp = so!(so!(c.clone()), tp.clone(),);
+
+        let ops = [
+            Operation::Select(dances.clone()),
+            Operation::Select(v.clone()),
+            Operation::Merge(so!(v), so!(dances)),
+            Operation::Select(mary.clone()),
+            Operation::Merge(v1, so!(mary)),
+            Operation::Select(pres.clone()),
+            Operation::Merge(so!(pres), vp.clone()),
+            Operation::Select(c.clone()),
+            Operation::Merge(so!(c), tp),
+            Operation::Transfer(cp.clone()),
+        ];
+
+        //  The v*P is a strong phase whose complement has not been transferred, so the CP cannot be transferred.
+        let before = replay(&il.ug, stages[0].la.clone(), &ops[..9]).unwrap();
+        let last = before.last().unwrap();
+        let lw = LabeledWorkspace::with_ug(&il.ug, &last.w);
+        assert_eq!(lw.untransferred_phase_in(&cp).map(|(phase, _)| phase), Some(&vp));
+
+        assert_eq!(il.ug.transfer_policy, TransferPolicy::Strict);
+        assert_eq!(last.apply(&il.ug, &ops[9]), Err(OperationError::UntransferredPhase(vp.clone())));
+        assert_eq!(replay(&il.ug, stages[0].la.clone(), &ops).unwrap_err().step, 10);
+        assert!(is_derivation(&il, &stages));
+
+        //  It can be under the permissive policy, for replay and search as well as for the checker.
+        il.ug.transfer_policy = TransferPolicy::Permissive;
+        let old = replay(&il.ug, stages[0].la.clone(), &ops).unwrap();
+        assert!(is_derivation(&il, &old));
         assert!(is_derivation(&il, &stages));
-        assert!(converges(&stages));
+
+        let checkpoints = [ old[0].clone(), old[5].clone(), old[9].clone(), old[10].clone() ];
+        assert_eq!(fill_gaps(&il, &checkpoints, 5).unwrap().len(), old.len());
+
+        il.ug.transfer_policy = TransferPolicy::Strict;
+        assert_eq!(
+            check_derivation(&il, &old),
+            Err(DerivationError::NoMatchingRule { stage: 10, reason: None })
+        );
+        assert_eq!(fill_gaps(&il, &checkpoints, 5), Err(DerivationError::Unreachable { stage: 3, bound: 5 }));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn phase_heads() {
+        init();
+
+        let (mut il, stages) = mary_dances();
+        assert_eq!(il.ug.phase_heads, fset!("C", "v*"));
+
+        //  The v*P before Cyclic-Transfer.
+        let w = &stages[5].w;
+        let vp = w.iter().next().unwrap();
+        assert!(is_strong_phase(&il.ug, vp, w));
+
+        //  Only C is a phase head, so the v*P cannot be cyclic-transferred.
+        il.ug.phase_heads = fset!("C");
+        assert!(!is_strong_phase(&il.ug, vp, w));
+        assert_eq!(
+            check_derivation(&il, &stages),
+            Err(DerivationError::NoMatchingRule { stage: 6, reason: None })
+        );
+
+        //  D is a phase head as well. "Mary" has no complement, so nothing changes.
+        il.ug.phase_heads = fset!("C", "v*", "D");
+        let mary = so!(lit!(li!("Mary"; "D"; "Mary"), 1));
+        assert!(is_strong_phase(&il.ug, &mary, &stages[4].w));
+        assert!(is_derivation(&il, &stages));
+    }
+
+    #[test]
+    fn triggers_config() {
+        init();
+
+        let default = BasicTriggers::default();
+        assert_eq!(default.probes(), &fset!("wh"));
+        assert_eq!(default.epps(), &fset!("EPP"));
+        assert_eq!(default.selector_prefix(), "=");
+
+        assert_eq!(BasicTriggers::new(fset!("wh"), fset!("EPP"), "".to_string()), Err(TriggersError::EmptySelectorPrefix));
+        assert_eq!(BasicTriggers::new(fset!("wh", "EPP"), fset!("EPP"), "=".to_string()), Err(TriggersError::ProbeAndEpp(f!("EPP"))));
+        assert_eq!(BasicTriggers::new(fset!("wh"), fset!("+EPP"), "+".to_string()), Err(TriggersError::StartsWithSelectorPrefix(f!("+EPP"))));
+
+        //  Lowercase "epp" is not EPP, so neither T nor "Mary" projects.
+        let t = lit!(li!(; "T", "+D", "epp";), 1);
+        let mary = lit!(li!("Mary"; "D"; "Mary"), 2);
+        let tp = so!(so!(t.clone()), so!(mary.clone()),);
+        let w = w!(tp.clone());
+        assert_eq!(default.triggers(&tp, &w), Err(CandsError::NeitherProjects(tp.clone())));
+
+        let mut ug = UniversalGrammar::<BasicTriggers>::new(fset!("Mary"), fset!("D", "T", "+D", "epp"), fset!());
+        assert_eq!(
+            ug.trigger_warnings(),
+            vec![
+                TriggersWarning::Undeclared(f!("wh")),
+                TriggersWarning::Undeclared(f!("EPP")),
+                TriggersWarning::Misspelled { feature: f!("epp"), trigger: f!("EPP") },
+            ]
+        );
+
+        //  With "epp" as the EPP-like feature and "+" as the selector prefix, "Mary" checks "+D", and "epp" is left.
+        ug.triggers = BasicTriggers::new(fset!(), fset!("epp"), "+".to_string()).unwrap();
+        assert_eq!(ug.trigger_warnings(), vec![]);
+        assert_eq!(ug.triggers.triggers(&tp, &w), Ok(fset!("epp")));
+        assert_eq!(ug.triggers.label_of(&tp, &w), Ok(t.clone()));
+
+        let lw = LabeledWorkspace::with_ug(&ug, &w);
+        assert_eq!(lw.label_of(&tp), Ok(t.clone()));
+
+        //  The operations and relations outside a labeled workspace use the same trigger features.
+        assert_eq!(triggered_merge(&ug, so!(t.clone()), so!(mary.clone()), &w), Ok(tp.clone()));
+        assert!(so!(mary.clone()).is_complement_of(&lw, &so!(t.clone()), &tp));
+        assert!(tp.is_maximal_projection_of(&lw, &t));
+
+        let default_ug = UniversalGrammar::<BasicTriggers>::new(set!(), set!(), set!());
+        assert_eq!(triggered_merge(&default_ug, so!(t.clone()), so!(mary.clone()), &w), Err(CandsError::NoTriggers(so!(t.clone()))));
+        assert!(!so!(mary).is_complement_of(&LabeledWorkspace::with_ug(&default_ug, &w), &so!(t), &tp));
+    }
+
+    #[test]
+    fn trigger_warnings_logged() {
+        init();
+
+        let ug = UniversalGrammar::<BasicTriggers>::new(fset!(), fset!("epp"), fset!());
+        let il = ILanguage { lex: set!(), ug };
+        let stages: Vec<Stage> = vec![ Stage { la: LexicalArray::new(set!()), w: w!() } ];
+
+        //  The warnings are logged when a derivation is checked, which still succeeds.
+        take_warnings();
+        assert!(is_derivation(&il, &stages));
+        let logged = take_warnings();
+        let expected = il.ug.trigger_warnings();
+        assert_eq!(expected.len(), 3);
+        for warning in expected {
+            assert!(logged.iter().any(|line| line.ends_with(&warning.to_string())), "{} is not logged", warning);
+        }
+
+        //  Without likely mistakes, nothing is logged.
+        let (mut il, stages) = mary_dances();
+        il.ug.syn_f.extend(fset!("wh", "EPP"));
+        assert!(is_derivation(&il, &stages));
+        assert_eq!(take_warnings(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn token_errors() {
+        init();
+
+        let (mut il, stages) = mary_dances();
+
+        //  Two tokens of "Mary" with the same index, which only differ in their shorthands.
+        let mary = li!("Mary"; "D"; "Mary"; "Mary");
+        il.lex.insert(mary.clone());
+        let mut bad = stages[..1].to_vec();
+        bad[0].la.insert(lit!(mary.clone(), 1));
+        assert!(matches!(
+            check_derivation(&il, &bad),
+            Err(DerivationError::SharedTokenIndex(lit)) if lit.k == 1
+        ));
+
+        //  A different index is fine.
+        let mut ok = stages.clone();
+        for stage in ok.iter_mut() {
+            stage.la.insert(lit!(mary.clone(), 6));
+        }
+        assert!(is_derivation(&il, &ok));
+
+        //  A token that suddenly appears in the lexical array.
+        let mut bad = stages.clone();
+        bad[4].la.insert(lit!(mary.clone(), 6));
+        assert_eq!(
+            check_derivation(&il, &bad),
+            Err(DerivationError::UnknownToken { stage: 5, lit: lit!(mary.clone(), 6) })
+        );
+
+        //  A token that suddenly appears in the workspace.
+        let mut bad = stages.clone();
+        bad[2].w.insert(so!(lit!(mary.clone(), 6)));
+        assert_eq!(
+            check_derivation(&il, &bad),
+            Err(DerivationError::UnknownToken { stage: 3, lit: lit!(mary, 6) })
+        );
+    }
+
+    #[test]
+    fn validate1() {
+        init();
+
+        let (mut il, stages) = mary_dances();
+
+        assert_eq!(il.validate(), Ok(()));
+
+        //  "Sue" is neither in SEM-F nor in PHON-F, and "N" is not in SYN-F.
+        let sue = li!("Sue"; "N"; "Sue");
+        il.lex.insert(sue.clone());
+
+        let undeclared = il.validate().unwrap_err();
+        assert_eq!(
+            undeclared.iter()
+                .map(|u| (u.li.clone(), u.component, u.feature.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (sue.clone(), FeatureComponent::Sem, f!("Sue")),
+                (sue.clone(), FeatureComponent::Syn, f!("N")),
+                (sue, FeatureComponent::Phon, f!("Sue")),
+            ]
+        );
+
+        assert_eq!(
+            check_derivation(&il, &stages),
+            Err(DerivationError::InvalidLexicon(undeclared))
+        );
+    }
+
+    #[test]
+    fn merge_kinds() {
+        init();
+
+        let (il, stages) = mary_dances_epp();
+
+        let report = check_derivation(&il, &stages).unwrap();
+        assert_eq!(
+            report.steps.iter()
+                .filter_map(|step| step.merge)
+                .collect::<Vec<_>>(),
+            vec![ MergeKind::External, MergeKind::External, MergeKind::External, MergeKind::Internal ]
+        );
+        assert_eq!(report.count_merges(MergeKind::Internal), 1);
+        assert!(report.to_string().contains("internal Merge(A, B)"));
+    }
+
+    #[test]
+    fn tree1() {
+        init();
+
+        let (il, stages) = mary_dances();
+        let w = &stages[10].w;
+        let root = w.iter().next().unwrap();
+
+        let tree = Tree::new(&il.ug.triggers, root, w);
+        assert_eq!(tree.len(), 10);
+
+        //  The same relations as for occurrences.
+        for a in tree.nodes() {
+            let occ_a = Occurrence::new(tree.path(a));
+            assert!(occ_a.check());
+            assert_eq!(tree.label(a), il.ug.triggers.label_of(tree.so(a), w).ok().as_ref());
+
+            for b in tree.nodes() {
+                let occ_b = Occurrence::new(tree.path(b));
+                assert_eq!(tree.immediately_contains(a, b), occ_a.immediately_contains(&occ_b));
+                assert_eq!(tree.contains(a, b), occ_a.contains(&occ_b));
+                assert_eq!(tree.sisters_with(a, b), occ_a.sisters_with(&occ_b));
+                assert_eq!(tree.c_commands(a, b), occ_a.c_commands(&occ_b));
+                assert_eq!(tree.asymmetrically_c_commands(a, b), occ_a.asymmetrically_c_commands(&occ_b));
+            }
+        }
+
+        let c = lit!(li!("C"; "C", "=T";), 5);
+        assert!(tree.is_maximal_projection_of(tree.root(), &c));
+        assert!(tree.nodes().all(|id| tree.is_final(id)));
+    }
+
+    #[test]
+    fn interned1() {
+        init();
+
+        let (il, stages) = mary_dances();
+        let interned: Vec<Stage<InternedSO>> = stages.iter().map(Stage::from).collect();
+
+        let report = check_derivation(&il, &stages).unwrap();
+        let interned_report = check_derivation(&il, &interned).unwrap();
+
+        //  The same steps, up to the representation of syntactic objects.
+        assert_eq!(interned_report.steps.len(), report.steps.len());
+        for (step, interned_step) in report.steps.iter().zip(interned_report.steps.iter()) {
+            assert_eq!(interned_step.merge, step.merge);
+            match (&step.op, &interned_step.op) {
+                (Operation::Select(lit), Operation::Select(interned_lit)) =>
+                    assert_eq!(interned_lit, lit),
+                (Operation::Merge(a, b), Operation::Merge(interned_a, interned_b)) => {
+                    assert_eq!(&SyntacticObject::from(interned_a), a);
+                    assert_eq!(&SyntacticObject::from(interned_b), b);
+                },
+                (Operation::Transfer(so), Operation::Transfer(interned_so)) |
+                (Operation::CyclicTransfer(so), Operation::CyclicTransfer(interned_so)) =>
+                    assert_eq!(&SyntacticObject::from(interned_so), so),
+                _ => panic!("Different operations at stage {}.", step.stage),
+            }
+        }
+
+        //  Round trip.
+        let stages2: Vec<Stage> = interned.iter().map(Stage::from).collect();
+        assert_eq!(stages2, stages);
+    }
+
+    #[test]
+    fn cands_errors() {
+        init();
+
+        let (il, stages) = mary_dances();
+        let w = &stages[2].w;
+        let dances = so!(lit!(li!("dances"; "V"; "dances"), 2));
+        let v = so!(lit!(li!("v*"; "v*", "=V", "=D";), 3));
+        let mary = so!(lit!(li!("Mary"; "D"; "Mary"), 1));
+
+        //  Not binary.
+        let abc = so!(dances.clone(), v.clone(), mary.clone(),);
+        assert_eq!(il.ug.triggers.triggers(&abc, w), Err(CandsError::NotBinary(abc.clone())));
+        assert_eq!(transfer(&il.ug, &abc, abc.clone(), w), Err(CandsError::NotBinary(abc.clone())));
+
+        //  Not in the workspace.
+        let vp = so!(v.clone(), dances.clone(),);
+        assert_eq!(il.ug.triggers.label_of(&vp, w), Err(CandsError::NotInWorkspace(vp.clone())));
+
+        //  Neither "Mary" nor "dances" has trigger features.
+        let bad = so!(mary.clone(), dances.clone(),);
+        assert_eq!(il.ug.triggers.triggers(&bad, w), Err(CandsError::NeitherProjects(bad.clone())));
+
+        //  "Mary" can't check "=v*".
+        let pres = so!(lit!(li!("PRES"; "T", "=v*";), 4));
+        let bad = so!(pres.clone(), mary.clone(),);
+        assert_eq!(il.ug.triggers.triggers(&bad, w), Err(CandsError::NoCheckableTrigger(bad.clone())));
+
+        //  Both v* and PRES have trigger features.
+        let bad = so!(v.clone(), pres,);
+        assert_eq!(il.ug.triggers.triggers(&bad, w), Err(CandsError::BothProject(bad.clone())));
+        assert_eq!(triggered_merge(&il.ug, v.clone(), v.clone(), w), Err(CandsError::IdenticalOperands(v.clone())));
+        assert_eq!(triggered_merge(&il.ug, v.clone(), bad.clone(), w), Err(CandsError::BothProject(bad.clone())));
+        assert_eq!(triggered_merge(&il.ug, mary.clone(), dances.clone(), w), Err(CandsError::NoTriggers(mary.clone())));
+
+        //  A lone v* has no complement.
+        assert_eq!(cyclic_transfer(&il.ug, v.clone(), w), Err(CandsError::NoComplement(v.clone())));
+    }
+
+}
@@ -1,9 +1,6 @@
+use std::cell::{ Cell };
 use std::collections::BTreeSet;
 
-use std::sync::{ Mutex };
-
-use once_cell::sync::{ Lazy };
-
 
 /// Type definition for convenience.
 pub type Set<T> = BTreeSet<T>;
@@ -27,14 +24,18 @@ pub(crate) use set; // https://stackoverflow.com/a/31749071
 
 
 
-/// Stack depth counter for logging macros.
-pub static LOG_STACK_DEPTH: Lazy<Mutex<usize>> = Lazy::new(|| Mutex::new(0));
+thread_local! {
+    /// Stack depth counter for logging macros.
+    ///
+    /// Each thread has its own counter, so that derivations can be checked on several threads at once (see [`crate::deriv::check_derivation_parallel`]).
+    pub static LOG_STACK_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
 
 /// Increase the stack depth counter.
 /// 
 /// You should call this at the beginning of a function where one of the logging macros are used.
 macro_rules! inc {
-    () => { *crate::utils::LOG_STACK_DEPTH.lock().unwrap() += 1; };
+    () => { crate::utils::LOG_STACK_DEPTH.with(|depth| depth.set(depth.get() + 1)); };
 }
 
 pub(crate) use inc;
@@ -43,7 +44,7 @@ pub(crate) use inc;
 /// 
 /// You should call this at the end of a function where one of the logging macros are used.
 macro_rules! dec {
-    () => { *crate::utils::LOG_STACK_DEPTH.lock().unwrap() -= 1; };
+    () => { crate::utils::LOG_STACK_DEPTH.with(|depth| depth.set(depth.get() - 1)); };
 }
 
 pub(crate) use dec;
@@ -53,7 +54,7 @@ macro_rules! my_trace {
     ( $($e:expr),*) => {
         let s = format!($($e),*);
         for line in s.lines() {
-            log::trace!("{} {}", std::iter::repeat(">").take(*crate::utils::LOG_STACK_DEPTH.lock().unwrap()).collect::<String>(), line);
+            log::trace!("{} {}", ">".repeat(crate::utils::LOG_STACK_DEPTH.with(|depth| depth.get())), line);
         }
     };
 }
@@ -63,7 +64,7 @@ macro_rules! my_debug {
     ( $($e:expr),*) => {
         let s = format!($($e),*);
         for line in s.lines() {
-            log::debug!("{} {}", std::iter::repeat(">").take(*crate::utils::LOG_STACK_DEPTH.lock().unwrap()).collect::<String>(), line);
+            log::debug!("{} {}", ">".repeat(crate::utils::LOG_STACK_DEPTH.with(|depth| depth.get())), line);
         }
     };
 }
@@ -73,7 +74,17 @@ macro_rules! my_info {
     ($($e:expr),*) => {
         let s = format!($($e),*);
         for line in s.lines() {
-            log::info!("{} {}", std::iter::repeat(">").take(*crate::utils::LOG_STACK_DEPTH.lock().unwrap()).collect::<String>(), line);
+            log::info!("{} {}", ">".repeat(crate::utils::LOG_STACK_DEPTH.with(|depth| depth.get())), line);
+        }
+    };
+}
+
+/// Wrapper around the `log::warn` macro.
+macro_rules! my_warn {
+    ($($e:expr),*) => {
+        let s = format!($($e),*);
+        for line in s.lines() {
+            log::warn!("{} {}", ">".repeat(crate::utils::LOG_STACK_DEPTH.with(|depth| depth.get())), line);
         }
     };
 }
@@ -83,7 +94,7 @@ macro_rules! my_error {
     ($($e:expr),*) => {
         let s = format!($($e),*);
         for line in s.lines() {
-            log::error!("{} {}", std::iter::repeat(">").take(*crate::utils::LOG_STACK_DEPTH.lock().unwrap()).collect::<String>(), line);
+            log::error!("{} {}", ">".repeat(crate::utils::LOG_STACK_DEPTH.with(|depth| depth.get())), line);
         }
     };
 }
@@ -91,6 +102,7 @@ macro_rules! my_error {
 pub(crate) use my_trace;
 pub(crate) use my_debug;
 pub(crate) use my_info;
+pub(crate) use my_warn;
 pub(crate) use my_error;
 
 